@@ -1,14 +1,21 @@
-use crate::parser::TableSchema;
+use crate::parser::{EnumType, ForeignKey, SqlDialect, TableColumn, TableSchema};
+use sqlparser::ast::{ArrayElemTypeDef, DataType, ExactNumberInfo, TimezoneInfo};
 use std::collections::HashMap;
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use tera::{Context, Tera};
 
-/// Generates all domain, controller, and common modules based on the provided table schema.
+/// Generates all domain, controller, and common modules based on the provided table schemas.
 pub fn generate_code(
     schema: &[TableSchema],
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     for table in schema {
+        report_string_fallbacks(table);
+        report_skipped_relations(table);
+
         // domain
         generate_model(table, output_dir)?;
         generate_repository(table, output_dir)?;
@@ -26,7 +33,7 @@ pub fn generate_code(
         generate_impl_repository(table, output_dir)?;
 
         // tests
-        generate_tests(table, output_dir)?;
+        generate_tests(table, schema, output_dir)?;
     }
 
     // common
@@ -37,6 +44,9 @@ pub fn generate_code(
     generate_app(schema, output_dir)?;
     generate_app_state(schema, output_dir)?;
     generate_bootstrap(schema, output_dir)?;
+    generate_enums(schema, output_dir)?;
+    generate_pg_format(schema, output_dir)?;
+    generate_naive_ts_format(schema, output_dir)?;
 
     Ok(())
 }
@@ -54,11 +64,16 @@ pub fn generate_bootstrap(
         .map(|s| {
             let mut map = std::collections::HashMap::new();
             map.insert("module", s.module_name.clone());
+            map.insert("path", module_path(s));
             map.insert("struct_name", s.struct_name.clone());
             map
         })
         .collect::<Vec<_>>();
     context.insert("modules", &modules);
+    insert_database(
+        &mut context,
+        schemas.first().map(|s| s.dialect).unwrap_or_default(),
+    );
 
     let bootstrap_code = tera.render("bootstrap.tera", &context)?;
     let bootstrap_dir = Path::new(output_dir).join("common");
@@ -68,6 +83,109 @@ pub fn generate_bootstrap(
     Ok(())
 }
 
+/// Generates the `common/enums.rs` file holding the enum types referenced by the tables.
+/// Nothing is written when no column uses an enum type.
+pub fn generate_enums(
+    schemas: &[TableSchema],
+    output_dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut enums = Vec::new();
+    for enum_type in schemas
+        .iter()
+        .flat_map(|s| &s.columns)
+        .filter_map(|col| col.enum_type.as_ref())
+    {
+        if !enums.iter().any(|e: &&EnumType| e.name == enum_type.name) {
+            enums.push(enum_type);
+        }
+    }
+    if enums.is_empty() {
+        return Ok(());
+    }
+
+    let tera = Tera::new("templates/**/*")?;
+    let mut context = Context::new();
+
+    let enums = enums
+        .iter()
+        .map(|e| {
+            let variants = e
+                .variants
+                .iter()
+                .map(|variant| {
+                    serde_json::json!({
+                        "name": variant.name,
+                        // Rust string literal of the label, for the rename attributes
+                        "label": format!("{:?}", variant.label),
+                    })
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({
+                "name": format!("{:?}", e.name),
+                "type_name": e.type_name,
+                "rename_all": e.rename_all.unwrap_or_default(),
+                "variants": variants,
+            })
+        })
+        .collect::<Vec<_>>();
+    context.insert("enums", &enums);
+
+    let enums_code = tera.render("enums.tera", &context)?;
+    let common_dir = Path::new(output_dir).join("common");
+    fs::create_dir_all(&common_dir)?;
+    fs::write(common_dir.join("enums.rs"), enums_code)?;
+
+    Ok(())
+}
+
+/// Generates the `common/pg_format.rs` file holding the serde modules of the sqlx types
+/// without serde support. Nothing is written when no column uses such a type.
+pub fn generate_pg_format(
+    schemas: &[TableSchema],
+    output_dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let used = schemas.iter().any(|schema| {
+        schema
+            .columns
+            .iter()
+            .any(|col| pg_format(&column_type(schema, col)).is_some())
+    });
+    if !used {
+        return Ok(());
+    }
+
+    let tera = Tera::new("templates/**/*")?;
+    let pg_format_code = tera.render("pg_format.tera", &Context::new())?;
+    let common_dir = Path::new(output_dir).join("common");
+    fs::create_dir_all(&common_dir)?;
+    fs::write(common_dir.join("pg_format.rs"), pg_format_code)?;
+
+    Ok(())
+}
+
+/// Generates the `common/naive_ts_format.rs` serde module of the timestamps without time zone.
+/// Nothing is written when no column holds such a timestamp.
+pub fn generate_naive_ts_format(
+    schemas: &[TableSchema],
+    output_dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !schemas
+        .iter()
+        .flat_map(|s| &s.columns)
+        .any(is_naive_datetime)
+    {
+        return Ok(());
+    }
+
+    let tera = Tera::new("templates/**/*")?;
+    let naive_ts_format_code = tera.render("naive_ts_format.tera", &Context::new())?;
+    let common_dir = Path::new(output_dir).join("common");
+    fs::create_dir_all(&common_dir)?;
+    fs::write(common_dir.join("naive_ts_format.rs"), naive_ts_format_code)?;
+
+    Ok(())
+}
+
 /// Generates the `common/app_state.rs` struct for holding application state.
 pub fn generate_app_state(
     schemas: &[TableSchema],
@@ -81,6 +199,8 @@ pub fn generate_app_state(
         .map(|s| s.module_name.clone())
         .collect::<Vec<_>>();
     context.insert("modules", &modules);
+    insert_module_paths(&mut context, schemas);
+    insert_struct_names(&mut context, schemas);
 
     let app_state_code = tera.render("app_state.tera", &context)?;
     let app_state_dir = Path::new(output_dir).join("common");
@@ -103,6 +223,16 @@ pub fn generate_app(
         .map(|s| s.module_name.clone())
         .collect::<Vec<_>>();
     context.insert("modules", &modules);
+    insert_module_paths(&mut context, schemas);
+    insert_struct_names(&mut context, schemas);
+
+    // Modules exposing nested relation routes, merged at the router root
+    let relation_modules = schemas
+        .iter()
+        .filter(|s| !relation_keys(s).is_empty())
+        .map(|s| s.module_name.clone())
+        .collect::<Vec<_>>();
+    context.insert("relation_modules", &relation_modules);
 
     let app_code = tera.render("app.tera", &context)?;
     fs::write(Path::new(output_dir).join("app.rs"), app_code)?;
@@ -110,7 +240,8 @@ pub fn generate_app(
     Ok(())
 }
 
-/// Generates the `domains.rs` file exposing modules.
+/// Generates the `domains.rs` file exposing modules. Modules grouped per schema are exposed
+/// through a `domains/<schema>.rs` file listing them.
 pub fn generate_domains(
     schemas: &[TableSchema],
     output_dir: &str,
@@ -118,37 +249,108 @@ pub fn generate_domains(
     let tera = Tera::new("templates/**/*")?;
     let mut context = Context::new();
 
-    let modules = schemas
-        .iter()
-        .map(|s| s.module_name.clone())
-        .collect::<Vec<_>>();
+    let mut modules = Vec::new();
+    let mut groups: Vec<(&String, Vec<String>)> = Vec::new();
+    for schema in schemas {
+        match &schema.schema_module {
+            Some(schema_module) => {
+                match groups.iter_mut().find(|(group, _)| *group == schema_module) {
+                    Some((_, group_modules)) => group_modules.push(schema.module_name.clone()),
+                    None => {
+                        modules.push(schema_module.clone());
+                        groups.push((schema_module, vec![schema.module_name.clone()]));
+                    }
+                }
+            }
+            None => modules.push(schema.module_name.clone()),
+        }
+    }
     context.insert("modules", &modules);
 
     let domains_code = tera.render("domains.tera", &context)?;
     fs::write(Path::new(output_dir).join("domains.rs"), domains_code)?;
 
+    for (schema_module, group_modules) in groups {
+        context.insert("modules", &group_modules);
+        let group_code = tera.render("domains.tera", &context)?;
+        let domains_dir = Path::new(output_dir).join("domains");
+        fs::create_dir_all(&domains_dir)?;
+        fs::write(
+            domains_dir.join(format!("{}.rs", schema_module)),
+            group_code,
+        )?;
+    }
+
     Ok(())
 }
 
+/// Generates the `<module>.rs` feature file for each table schema.
 pub fn generate_feature(
     schemas: &[TableSchema],
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let tera = Tera::new("templates/**/*")?;
 
-    let mut context = Context::new();
+    for schema in schemas {
+        let mut context = Context::new();
+
+        // Insert basic context values
+        context.insert("struct_name", &schema.struct_name);
+        context.insert("module_name", &schema.module_name);
+        context.insert("module_path", &module_path(schema));
+        insert_relations(&mut context, schema);
+
+        let feature_code = tera.render("feature.tera", &context)?;
+
+        let feature_file_name = format!("{}.rs", schema.module_name);
+        // Grouped modules are written in place, next to their schema module
+        let feature_dir = match &schema.schema_module {
+            Some(schema_module) => Path::new(output_dir).join("domains").join(schema_module),
+            None => Path::new(output_dir).to_path_buf(),
+        };
+        fs::create_dir_all(&feature_dir)?;
+        fs::write(feature_dir.join(feature_file_name), feature_code)?;
+    }
 
-    let schema = &schemas[0]; // Assuming we only generate one feature per module
-    // Insert basic context values
-    context.insert("struct_name", &schema.struct_name);
-    context.insert("module_name", &schema.module_name);
+    Ok(())
+}
 
-    let feature_code = tera.render("feature.tera", &context)?;
+/// Returns the path of a table's module under `crate::domains`, e.g. `todo`, or `app::todo`
+/// when grouped per schema.
+fn module_path(schema: &TableSchema) -> String {
+    match &schema.schema_module {
+        Some(schema_module) => format!("{}::{}", schema_module, schema.module_name),
+        None => schema.module_name.clone(),
+    }
+}
 
-    let feature_file_name = format!("{}.rs", schema.module_name.to_lowercase());
-    fs::write(Path::new(output_dir).join(feature_file_name), feature_code)?;
+/// Returns the directory of a table's module files, e.g. `domains/todo`, or `domains/app/todo`
+/// when grouped per schema.
+fn module_dir(output_dir: &str, schema: &TableSchema) -> PathBuf {
+    let domains_dir = Path::new(output_dir).join("domains");
+    match &schema.schema_module {
+        Some(schema_module) => domains_dir.join(schema_module).join(&schema.module_name),
+        None => domains_dir.join(&schema.module_name),
+    }
+}
 
-    Ok(())
+/// Inserts `struct_names`: the struct name of each module, naming its `ApiDoc` and
+/// `ServiceTrait`, keyed by module name.
+fn insert_struct_names(context: &mut Context, schemas: &[TableSchema]) {
+    let struct_names = schemas
+        .iter()
+        .map(|s| (s.module_name.clone(), s.struct_name.clone()))
+        .collect::<HashMap<_, _>>();
+    context.insert("struct_names", &struct_names);
+}
+
+/// Inserts `module_paths`: the path under `crate::domains` of each module, keyed by module name.
+fn insert_module_paths(context: &mut Context, schemas: &[TableSchema]) {
+    let module_paths = schemas
+        .iter()
+        .map(|s| (s.module_name.clone(), module_path(s)))
+        .collect::<HashMap<_, _>>();
+    context.insert("module_paths", &module_paths);
 }
 
 /// Converts a snake_case string to PascalCase.
@@ -166,30 +368,672 @@ pub fn to_pascal_case(s: &str) -> String {
         .collect()
 }
 
-/// Maps SQL column types to equivalent Rust types.
-fn map_sql_type(sql_type: &str) -> &'static str {
-    let ty = sql_type.to_lowercase();
-
-    match ty.as_str() {
-        s if s.starts_with("char") || s.starts_with("varchar") || s.ends_with("text") => "String",
-        "uuid" => "uuid::Uuid",
-        "bool" | "boolean" => "bool",
-        "tinyint" => "i8",
-        "smallint" => "i16",
-        "mediumint" | "int" | "integer" => "i32",
-        "bigint" => "i64",
-        "decimal" | "numeric" => "f64",
-        "float" => "f32",
-        "double" => "f64",
-        "date" => "time::Date",
-        "datetime" | "timestamp" | "timestamptz" => "DateTime<Utc>", // <-- NO Option here
-        "time" => "String",
-        "year" => "i16",
-        s if s.starts_with("enum") || s.starts_with("set") => "String",
-        s if s.starts_with("binary") || s.starts_with("varbinary") => "Vec<u8>",
-        s if s.ends_with("blob") => "Vec<u8>",
-        "json" => "serde_json::Value",
-        _ => "String",
+/// Maps a parsed SQL column type to the equivalent Rust type, or `None` when the type has no
+/// mapping. Dates and times map to chrono types, PostgreSQL-specific types to sqlx ones.
+fn map_sql_type(data_type: &DataType) -> Option<&'static str> {
+    let rust_type = match data_type {
+        DataType::Character(_)
+        | DataType::Char(_)
+        | DataType::CharacterVarying(_)
+        | DataType::CharVarying(_)
+        | DataType::Varchar(_)
+        | DataType::Nvarchar(_)
+        | DataType::Text
+        | DataType::TinyText
+        | DataType::MediumText
+        | DataType::LongText
+        | DataType::String(_)
+        | DataType::FixedString(_)
+        | DataType::CharacterLargeObject(_)
+        | DataType::CharLargeObject(_)
+        | DataType::Clob(_) => "String",
+        DataType::Uuid => "uuid::Uuid",
+        DataType::Bool | DataType::Boolean => "bool",
+        DataType::TinyInt(_) => "i8",
+        DataType::Int2(_) | DataType::SmallInt(_) | DataType::Int16 => "i16",
+        DataType::MediumInt(_)
+        | DataType::Int(_)
+        | DataType::Int4(_)
+        | DataType::Integer(_)
+        | DataType::Int32 => "i32",
+        DataType::Int8(_) | DataType::BigInt(_) | DataType::Int64 => "i64",
+        // MySQL unsigned integers
+        DataType::TinyIntUnsigned(_) => "u8",
+        DataType::Int2Unsigned(_) | DataType::SmallIntUnsigned(_) => "u16",
+        DataType::MediumIntUnsigned(_)
+        | DataType::IntUnsigned(_)
+        | DataType::Int4Unsigned(_)
+        | DataType::IntegerUnsigned(_) => "u32",
+        DataType::BigIntUnsigned(_) | DataType::Int8Unsigned(_) => "u64",
+        DataType::Numeric(_)
+        | DataType::Decimal(_)
+        | DataType::Dec(_)
+        | DataType::BigNumeric(_)
+        | DataType::BigDecimal(_) => "rust_decimal::Decimal",
+        // FLOAT(p) is single precision up to 24 bits of mantissa, double precision otherwise
+        DataType::Float(Some(precision)) if *precision <= 24 => "f32",
+        DataType::Float4 | DataType::Real | DataType::Float32 => "f32",
+        DataType::Float(_)
+        | DataType::Float8
+        | DataType::Float64
+        | DataType::Double(_)
+        | DataType::DoublePrecision => "f64",
+        DataType::Date | DataType::Date32 => "chrono::NaiveDate",
+        DataType::Time(_, TimezoneInfo::None | TimezoneInfo::WithoutTimeZone) => {
+            "chrono::NaiveTime"
+        }
+        DataType::Time(_, TimezoneInfo::WithTimeZone | TimezoneInfo::Tz) => {
+            "sqlx::postgres::types::PgTimeTz<chrono::NaiveTime, chrono::FixedOffset>"
+        }
+        DataType::Interval => "sqlx::postgres::types::PgInterval",
+        DataType::Timestamp(_, TimezoneInfo::WithTimeZone | TimezoneInfo::Tz) => "DateTime<Utc>",
+        // Timestamps without time zone hold no offset, so they cannot decode as UTC
+        DataType::Timestamp(_, TimezoneInfo::None | TimezoneInfo::WithoutTimeZone)
+        | DataType::Datetime(_) => "chrono::NaiveDateTime",
+        DataType::Binary(_)
+        | DataType::Varbinary(_)
+        | DataType::Blob(_)
+        | DataType::TinyBlob
+        | DataType::MediumBlob
+        | DataType::LongBlob
+        | DataType::Bytes(_)
+        | DataType::Bytea => "Vec<u8>",
+        DataType::JSON | DataType::JSONB => "serde_json::Value",
+        DataType::Enum(..) | DataType::Set(_) => "String",
+        // PostgreSQL types the parser has no dedicated variant for
+        DataType::Custom(name, _) => match name.to_string().to_lowercase().as_str() {
+            "smallserial" | "serial2" => "i16",
+            "serial" | "serial4" => "i32",
+            "bigserial" | "serial8" => "i64",
+            "year" => "i16",
+            "citext" | "name" | "bpchar" => "String",
+            "inet" | "cidr" => "sqlx::types::ipnetwork::IpNetwork",
+            "macaddr" => "sqlx::types::mac_address::MacAddress",
+            "money" => "sqlx::postgres::types::PgMoney",
+            "oid" => "sqlx::postgres::types::Oid",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(rust_type)
+}
+
+/// Returns whether a column holds a timestamp with time zone, serialized through
+/// `crate::common::ts_format`.
+fn is_datetime(col: &TableColumn) -> bool {
+    col.type_override.is_none()
+        && matches!(
+            col.data_type,
+            DataType::Timestamp(_, TimezoneInfo::WithTimeZone | TimezoneInfo::Tz)
+        )
+}
+
+/// Returns whether a column holds a timestamp without time zone, serialized through
+/// `crate::common::naive_ts_format`.
+fn is_naive_datetime(col: &TableColumn) -> bool {
+    col.type_override.is_none()
+        && matches!(
+            col.data_type,
+            DataType::Timestamp(_, TimezoneInfo::None | TimezoneInfo::WithoutTimeZone)
+                | DataType::Datetime(_)
+        )
+}
+
+/// Largest precision `rust_decimal::Decimal` holds without rounding.
+const DECIMAL_MAX_PRECISION: u64 = 28;
+
+/// Returns the precision information of a NUMERIC/DECIMAL column, `None` for other types
+/// and for columns whose type is overridden by the rules.
+fn decimal_info(col: &TableColumn) -> Option<&ExactNumberInfo> {
+    if col.type_override.is_some() {
+        return None;
+    }
+    match &col.data_type {
+        DataType::Numeric(info)
+        | DataType::Decimal(info)
+        | DataType::Dec(info)
+        | DataType::BigNumeric(info)
+        | DataType::BigDecimal(info) => Some(info),
+        _ => None,
+    }
+}
+
+/// Returns whether a column maps to `rust_decimal::Decimal`.
+fn is_decimal(schema: &TableSchema, col: &TableColumn) -> bool {
+    !schema.decimal_as_f64 && decimal_info(col).is_some()
+}
+
+/// Returns the cast applied where a column is bound: NUMERIC/DECIMAL columns mapped to `f64`
+/// go through `FLOAT8` on PostgreSQL, since sqlx cannot encode NUMERIC as `f64`. MySQL converts
+/// bound doubles itself.
+fn sql_cast(schema: &TableSchema, col: &TableColumn) -> &'static str {
+    if schema.decimal_as_f64
+        && decimal_info(col).is_some()
+        && schema.dialect == SqlDialect::Postgres
+    {
+        "::FLOAT8"
+    } else {
+        ""
+    }
+}
+
+/// Returns the expression selecting a column: NUMERIC/DECIMAL columns mapped to `f64` are cast
+/// to a double precision type, since sqlx cannot decode them as `f64`.
+fn select_field(schema: &TableSchema, col: &TableColumn) -> String {
+    let name = &col.sql_name;
+    if !schema.decimal_as_f64 || decimal_info(col).is_none() {
+        return name.clone();
+    }
+    match schema.dialect {
+        SqlDialect::Postgres => format!("{}::FLOAT8 AS {}", name, name),
+        SqlDialect::MySql => format!("CAST({} AS DOUBLE) AS {}", name, name),
+        SqlDialect::Sqlite => format!("CAST({} AS REAL) AS {}", name, name),
+    }
+}
+
+/// Returns whether a column is part of the Update DTO and the UPDATE statement: key columns
+/// identify the row and are never updated, and the rules' `update_special_fields` are left out.
+fn is_updatable(schema: &TableSchema, col: &TableColumn) -> bool {
+    !schema.primary_key.contains(&col.name)
+        && !schema
+            .update_special_fields
+            .as_ref()
+            .is_some_and(|skip| skip.contains(&col.name))
+}
+
+/// Returns the Rust field of a column without the `r#` of raw identifiers, for names built
+/// from it such as `find_by_type`.
+fn field_ident(col: &TableColumn) -> &str {
+    col.field_name.trim_start_matches("r#")
+}
+
+/// Inserts the column comment of a field map, as `comment` for doc comments and as
+/// `description`, a Rust string literal for `#[schema(description)]`; both are empty when the
+/// column has no comment.
+fn insert_comment(map: &mut HashMap<&str, String>, col: &TableColumn) {
+    map.insert("comment", col.comment.clone().unwrap_or_default());
+    map.insert(
+        "description",
+        col.comment
+            .as_ref()
+            .map(|comment| format!("{:?}", comment))
+            .unwrap_or_default(),
+    );
+}
+
+/// Returns the column name as a Rust string literal when the Rust field is named differently,
+/// for `#[sqlx(rename)]` and `#[serde(rename)]`, otherwise an empty string.
+fn field_rename(col: &TableColumn) -> String {
+    if field_ident(col) == col.name {
+        String::new()
+    } else {
+        format!("{:?}", col.name)
+    }
+}
+
+/// Maps a column to its Rust type: the rules' type override when there is one,
+/// `sqlx::types::Json<T>` for JSON columns typed by the rules' `json_types`, `f64` for
+/// NUMERIC/DECIMAL columns of tables setting `decimal_as_f64`, `sqlite_type` for SQLite
+/// tables, otherwise `map_column_type`.
+pub fn column_type(schema: &TableSchema, col: &TableColumn) -> String {
+    if let Some(type_override) = &col.type_override {
+        type_override.rust_type.clone()
+    } else if let Some(json_type) = &col.json_type {
+        format!("sqlx::types::Json<{}>", json_type)
+    } else if schema.decimal_as_f64 && decimal_info(col).is_some() {
+        "f64".to_string()
+    } else if schema.dialect == SqlDialect::Sqlite {
+        sqlite_type(&col.data_type).to_string()
+    } else {
+        map_column_type(&col.data_type, col.enum_type.as_ref())
+    }
+}
+
+/// Maps a column to the Rust type of its DTO fields: the document type itself for JSON columns
+/// typed by the rules' `json_types`, otherwise `column_type`.
+fn dto_type(schema: &TableSchema, col: &TableColumn) -> String {
+    match &col.json_type {
+        Some(json_type) => json_type.clone(),
+        None => column_type(schema, col),
+    }
+}
+
+/// Returns the expression binding a DTO value to a column, wrapping the documents of typed
+/// JSON columns in `sqlx::types::Json`; `is_optional` tells whether the value is an `Option`.
+fn dto_bind(col: &TableColumn, expr: &str, is_optional: bool) -> String {
+    match (&col.json_type, is_optional) {
+        (None, _) => expr.to_string(),
+        (Some(_), true) => format!("{}.map(sqlx::types::Json)", expr),
+        (Some(_), false) => format!("sqlx::types::Json({})", expr),
+    }
+}
+
+/// Maps a column type to its Rust type, looking through arrays: `TEXT[]` becomes `Vec<String>`.
+/// Multi-dimensional arrays, rejected by the parser unless overridden, map like one-dimensional
+/// ones. The column's enum type replaces the custom type it names.
+fn map_column_type(data_type: &DataType, enum_type: Option<&EnumType>) -> String {
+    match data_type {
+        DataType::Array(
+            ArrayElemTypeDef::SquareBracket(element, _)
+            | ArrayElemTypeDef::AngleBracket(element)
+            | ArrayElemTypeDef::Parenthesis(element),
+        ) => match element.as_ref() {
+            DataType::Array(_) => map_column_type(element, enum_type),
+            element => format!("Vec<{}>", map_column_type(element, enum_type)),
+        },
+        DataType::Array(ArrayElemTypeDef::None) => "Vec<String>".to_string(),
+        DataType::Custom(..) if let Some(enum_type) = enum_type => enum_type.type_name.clone(),
+        data_type => map_sql_type(data_type).unwrap_or("String").to_string(),
+    }
+}
+
+/// Maps a SQLite column type to its Rust type. Types sqlx decodes from SQLite's storage classes,
+/// such as booleans, dates and UUIDs, keep their mapping; the others follow SQLite's type
+/// affinity rules, applied in order to the declared type name:
+/// `INT` → `i64`, `CHAR`/`CLOB`/`TEXT` → `String`, `BLOB` or no type → `Vec<u8>`,
+/// `REAL`/`FLOA`/`DOUB` → `f64`, anything else (NUMERIC affinity) → `f64`.
+fn sqlite_type(data_type: &DataType) -> &'static str {
+    if let Some(
+        rust_type @ ("bool"
+        | "uuid::Uuid"
+        | "serde_json::Value"
+        | "chrono::NaiveDate"
+        | "chrono::NaiveTime"
+        | "chrono::NaiveDateTime"
+        | "DateTime<Utc>"),
+    ) = map_sql_type(data_type)
+    {
+        return rust_type;
+    }
+
+    let name = data_type.to_string().to_uppercase();
+    if name.contains("INT") {
+        "i64"
+    } else if ["CHAR", "CLOB", "TEXT"].iter().any(|s| name.contains(s)) {
+        "String"
+    } else if name.contains("BLOB") || matches!(data_type, DataType::Unspecified) {
+        "Vec<u8>"
+    } else {
+        "f64"
+    }
+}
+
+/// Returns whether a column falls back to `String` because its type, or the element type of
+/// its array type, has no Rust mapping.
+fn has_string_fallback(col: &TableColumn) -> bool {
+    let mut data_type = &col.data_type;
+    while let DataType::Array(
+        ArrayElemTypeDef::SquareBracket(element, _)
+        | ArrayElemTypeDef::AngleBracket(element)
+        | ArrayElemTypeDef::Parenthesis(element),
+    ) = data_type
+    {
+        data_type = element;
+    }
+
+    col.type_override.is_none()
+        && col.json_type.is_none()
+        && col.enum_type.is_none()
+        && map_sql_type(data_type).is_none()
+}
+
+/// Warns about the columns of a table falling back to `String`, which sqlx may fail to decode.
+fn report_string_fallbacks(schema: &TableSchema) {
+    // SQLite columns always map through their type affinity
+    if schema.dialect == SqlDialect::Sqlite {
+        return;
+    }
+
+    let columns = schema
+        .columns
+        .iter()
+        .filter(|col| has_string_fallback(col))
+        .map(|col| format!("`{}` ({})", col.name, col.sql_type))
+        .collect::<Vec<_>>();
+
+    if !columns.is_empty() {
+        eprintln!(
+            "Warning: table `{}`: no Rust type for {}, falling back to String; \
+             map them with `type_overrides`",
+            schema.table_name,
+            columns.join(", ")
+        );
+    }
+}
+
+/// Returns the serde module of `common/pg_format.rs` serializing a Rust type, for sqlx types
+/// without serde support, with the type documenting it in OpenAPI.
+fn pg_format(rust_type: &str) -> Option<(&'static str, &'static str)> {
+    match rust_type {
+        "sqlx::postgres::types::PgInterval" => Some(("interval", "Object")),
+        "sqlx::postgres::types::PgMoney" => Some(("money", "i64")),
+        "sqlx::postgres::types::Oid" => Some(("oid", "u32")),
+        ty if ty.starts_with("sqlx::postgres::types::PgTimeTz") => Some(("time_tz", "String")),
+        _ => None,
+    }
+}
+
+/// Returns the type documenting a column in OpenAPI when its Rust type has no `ToSchema`
+/// implementation: the `pg_format` schema, or `String` for network and MAC addresses.
+fn schema_type(schema: &TableSchema, col: &TableColumn) -> String {
+    let rust_type = column_type(schema, col);
+    if let Some((_, schema_type)) = pg_format(&rust_type) {
+        schema_type.to_string()
+    } else if rust_type.starts_with("sqlx::types::ipnetwork::")
+        || rust_type.starts_with("sqlx::types::mac_address::")
+    {
+        "String".to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Returns whether a column holds an array.
+fn is_array(col: &TableColumn) -> bool {
+    matches!(col.data_type, DataType::Array(_))
+}
+
+/// Returns whether a column holds a JSON document.
+fn is_json(col: &TableColumn) -> bool {
+    matches!(col.data_type, DataType::JSON | DataType::JSONB)
+}
+
+/// Inserts `type_imports`: the `use` paths required by the type overrides and enum types of
+/// the given columns.
+fn insert_type_imports<'a>(
+    context: &mut Context,
+    columns: impl IntoIterator<Item = &'a TableColumn>,
+) {
+    let mut imports = Vec::new();
+    for col in columns {
+        let column_imports = match (&col.type_override, &col.enum_type) {
+            (Some(type_override), _) => type_override.imports.clone(),
+            (None, Some(enum_type)) => {
+                vec![format!("crate::common::enums::{}", enum_type.type_name)]
+            }
+            (None, None) => Vec::new(),
+        };
+        for import in column_imports {
+            if !imports.contains(&import) {
+                imports.push(import);
+            }
+        }
+    }
+    imports.sort();
+
+    context.insert("type_imports", &imports);
+}
+
+/// Returns the columns appearing in the signatures of the repository, service and handlers:
+/// the primary key and the foreign key columns.
+fn signature_columns(schema: &TableSchema) -> impl Iterator<Item = &TableColumn> {
+    schema.columns.iter().filter(|col| {
+        schema.primary_key.contains(&col.name)
+            || schema
+                .foreign_keys
+                .iter()
+                .any(|fk| fk.columns.contains(&col.name))
+    })
+}
+
+/// Returns whether a column may be left out of the INSERT so the database fills its `DEFAULT`.
+/// Primary key columns keep their value so the created row can be read back.
+fn has_insert_default(schema: &TableSchema, col: &TableColumn) -> bool {
+    col.default.is_some() && !schema.primary_key.contains(&col.name)
+}
+
+/// Returns an expression for a key value that does not exist, used by tests.
+fn missing_key_value(rust_type: &str) -> String {
+    match rust_type {
+        "String" => "Uuid::new_v4().to_string()".to_string(),
+        "uuid::Uuid" => "Uuid::new_v4()".to_string(),
+        "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" => format!("{}::MAX", rust_type),
+        _ => "Default::default()".to_string(),
+    }
+}
+
+/// Returns an expression for a new key value of a Rust type, different on each evaluation, so
+/// tests creating several rows do not repeat keys: a random UUID, or an integer taken from one.
+fn fresh_key_value(rust_type: &str) -> Option<String> {
+    match rust_type {
+        "String" => Some("Uuid::new_v4().to_string()".to_string()),
+        "uuid::Uuid" => Some("Uuid::new_v4()".to_string()),
+        "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" => Some(format!(
+            "(Uuid::new_v4().as_u128() % {0}::MAX as u128) as {0}",
+            rust_type
+        )),
+        _ => None,
+    }
+}
+
+/// Returns a value of a Rust type sent by the tests: `Default::default()`, except for the sqlx
+/// types without a `Default` implementation, which get an explicit sample value.
+fn sample_value(rust_type: &str) -> String {
+    match rust_type {
+        "sqlx::types::ipnetwork::IpNetwork" => {
+            "\"127.0.0.1/32\".parse::<sqlx::types::ipnetwork::IpNetwork>().unwrap()".to_string()
+        }
+        "sqlx::types::mac_address::MacAddress" => {
+            "sqlx::types::mac_address::MacAddress::new([0; 6])".to_string()
+        }
+        ty if ty.starts_with("sqlx::postgres::types::PgTimeTz") => {
+            "sqlx::postgres::types::PgTimeTz { time: chrono::NaiveTime::MIN, offset: \
+             chrono::FixedOffset::east_opt(0).unwrap() }"
+                .to_string()
+        }
+        _ => "Default::default()".to_string(),
+    }
+}
+
+/// Inserts the sqlx types of the targeted database, shared by the files taking a pool or a
+/// transaction:
+/// - `dialect`: `postgres`, `mysql` or `sqlite`
+/// - `pool_type`: connection pool, e.g. `PgPool`
+/// - `db_type`: database, e.g. `Postgres` in `Transaction<'_, Postgres>`
+/// - `now`: SQL expression of the current time, e.g. `NOW()`
+fn insert_database(context: &mut Context, dialect: SqlDialect) {
+    context.insert("dialect", dialect.name());
+    context.insert("pool_type", dialect.pool_type());
+    context.insert("db_type", dialect.database_type());
+    context.insert("now", dialect.now());
+}
+
+/// Builds the assignments of the columns set to the authenticated user, each with `name` (the
+/// Rust field) and `value` (the user id expression, wrapped in `Some` for optional fields).
+fn user_fields<'a>(
+    schema: &TableSchema,
+    names: impl Iterator<Item = &'a String>,
+    is_optional: impl Fn(&TableColumn) -> bool,
+    user_id: &str,
+) -> Vec<HashMap<&'static str, String>> {
+    let mut fields: Vec<HashMap<&str, String>> = Vec::new();
+    for name in names {
+        let Some(col) = schema.columns.iter().find(|col| &col.name == name) else {
+            continue;
+        };
+        if fields.iter().any(|field| field["name"] == col.field_name) {
+            continue;
+        }
+        let value = if is_optional(col) {
+            format!("Some({})", user_id)
+        } else {
+            user_id.to_string()
+        };
+        let mut map = HashMap::new();
+        map.insert("name", col.field_name.clone());
+        map.insert("value", value);
+        fields.push(map);
+    }
+    fields
+}
+
+/// Inserts the primary key context shared by the dto, repository, service, api and test templates:
+/// - `pk_fields`: key columns, each with `name` (Rust field), `ident` (the field without `r#`),
+///   `column` (name in SQL), `rename` (see `field_rename`), `ty` (Rust type), `expr` (expression
+///   reading it from the key parameter) and `missing_value` (a value that does not exist, used
+///   by tests)
+/// - `is_composite_key`: whether the key spans several columns, in which case a
+///   `{{ struct_name }}Key` struct is generated and passed around as `key`
+/// - `pk_name`, `pk_type`: Rust field and type of the single key column
+/// - `pk_column`: name of the single key column in SQL
+/// - `pk_param`, `pk_param_type`: parameter carrying the key through handlers, services and repositories
+/// - `pk_path`: axum path segments capturing the key, e.g. `{id}` or `{user_id}/{role_id}`
+/// - `pk_url_format`: `format!` string for the key part of a URL, e.g. `{}/{}`
+/// - `pk_where`: SQL condition matching the key, e.g. `user_id = $1 AND role_id = $2`
+///   (`user_id = ? AND role_id = ?` on MySQL)
+/// - `pk_strategy`: how a new key is obtained on create: `payload` (part of the Create DTO),
+///   `database` (serial, identity or default), `uuid` or `uuid_string` (generated in Rust)
+fn insert_primary_key(context: &mut Context, schema: &TableSchema) {
+    let key_columns = schema
+        .primary_key
+        .iter()
+        .filter_map(|name| schema.columns.iter().find(|col| &col.name == name))
+        .collect::<Vec<_>>();
+
+    let is_composite_key = key_columns.len() > 1;
+
+    let pk_fields = key_columns
+        .iter()
+        .map(|col| {
+            let ty = column_type(schema, col);
+            let expr = if is_composite_key {
+                format!("key.{}", col.field_name)
+            } else {
+                col.field_name.clone()
+            };
+            let mut map = HashMap::new();
+            map.insert("name", col.field_name.clone());
+            map.insert("ident", field_ident(col).to_string());
+            map.insert("column", col.sql_name.clone());
+            map.insert("rename", field_rename(col));
+            insert_comment(&mut map, col);
+            map.insert("ty", ty.clone());
+            map.insert("expr", expr);
+            map.insert("missing_value", missing_key_value(&ty));
+            map
+        })
+        .collect::<Vec<_>>();
+
+    let pk_path = key_columns
+        .iter()
+        .map(|col| format!("{{{}}}", col.name))
+        .collect::<Vec<_>>()
+        .join("/");
+    let pk_url_format = vec!["{}"; key_columns.len()].join("/");
+    let pk_where = key_columns
+        .iter()
+        .enumerate()
+        .map(|(i, col)| format!("{} = {}", col.sql_name, schema.dialect.placeholder(i + 1)))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+
+    let (pk_param, pk_param_type) = if is_composite_key {
+        ("key".to_string(), format!("{}Key", schema.struct_name))
+    } else {
+        (
+            key_columns[0].field_name.clone(),
+            column_type(schema, key_columns[0]),
+        )
+    };
+
+    context.insert("pk_fields", &pk_fields);
+    context.insert("is_composite_key", &is_composite_key);
+    context.insert("pk_name", &key_columns[0].field_name);
+    context.insert("pk_column", &key_columns[0].sql_name);
+    context.insert("pk_type", &column_type(schema, key_columns[0]));
+    context.insert("pk_param", &pk_param);
+    context.insert("pk_param_type", &pk_param_type);
+    context.insert("pk_path", &pk_path);
+    context.insert("pk_url_format", &pk_url_format);
+    context.insert("pk_where", &pk_where);
+    context.insert("pk_strategy", schema.pk_strategy);
+}
+
+/// Inserts the `relations` context: one entry per single-column foreign key, with
+/// - `column`, `ty`: referencing column as an identifier (e.g. `type` for `r#type`) and its Rust
+///   type
+/// - `param`: Rust field of the referencing column, naming the parameter carrying it
+/// - `where`: SQL condition matching the column, e.g. `user_id = $1`
+/// - `referenced_table`, `referenced_column`, `referenced_module`, `referenced_struct`:
+///   the referenced table and its generated names, for rendering embedded relations
+/// - `path`: nested list route, e.g. `/user/{id}/todos`, followed by the column when several
+///   foreign keys reference the same table, e.g. `/user/{id}/todos/reviewer_id`
+fn insert_relations(context: &mut Context, schema: &TableSchema) {
+    let keys = relation_keys(schema);
+    let relations = keys
+        .iter()
+        .map(|&(fk, col, referenced_column)| {
+            let mut map = HashMap::new();
+            map.insert("column", field_ident(col).to_string());
+            map.insert("param", col.field_name.clone());
+            map.insert("ty", column_type(schema, col));
+            map.insert(
+                "where",
+                format!("{} = {}", col.sql_name, schema.dialect.placeholder(1)),
+            );
+            map.insert("referenced_table", fk.referenced_table.clone());
+            map.insert("referenced_column", referenced_column.clone());
+            map.insert("referenced_module", fk.referenced_module.clone());
+            map.insert("referenced_struct", fk.referenced_struct.clone());
+            let mut path = format!(
+                "/{}/{{{}}}/{}",
+                fk.referenced_module, referenced_column, schema.table_name
+            );
+            if keys
+                .iter()
+                .filter(|(other, _, _)| other.referenced_module == fk.referenced_module)
+                .count()
+                > 1
+            {
+                path = format!("{}/{}", path, field_ident(col));
+            }
+            map.insert("path", path);
+            map
+        })
+        .collect::<Vec<_>>();
+
+    context.insert("relations", &relations);
+}
+
+/// Returns the foreign keys generating relation endpoints, with their referencing column and
+/// the referenced column: the single-column keys, except those whose `find_by_<column>` finder
+/// would clash with the `find_by_id` key lookup.
+fn relation_keys(schema: &TableSchema) -> Vec<(&ForeignKey, &TableColumn, &String)> {
+    schema
+        .foreign_keys
+        .iter()
+        .filter_map(|fk| {
+            let ([column], [referenced_column]) =
+                (fk.columns.as_slice(), fk.referenced_columns.as_slice())
+            else {
+                return None;
+            };
+            let col = schema.columns.iter().find(|col| &col.name == column)?;
+            (field_ident(col) != "id").then_some((fk, col, referenced_column))
+        })
+        .collect()
+}
+
+/// Warns about the foreign keys of a table left without relation endpoints.
+fn report_skipped_relations(schema: &TableSchema) {
+    for fk in &schema.foreign_keys {
+        if fk.columns.len() != 1 || fk.referenced_columns.len() != 1 {
+            eprintln!(
+                "Warning: table `{}`: skipping relation endpoints for the multi-column \
+                 foreign key to `{}`",
+                schema.table_name, fk.referenced_table
+            );
+        } else if schema
+            .columns
+            .iter()
+            .any(|col| col.name == fk.columns[0] && field_ident(col) == "id")
+        {
+            eprintln!(
+                "Warning: table `{}`: skipping relation endpoints for the foreign key `{}` to \
+                 `{}`, whose finder would clash with `find_by_id`",
+                schema.table_name, fk.columns[0], fk.referenced_table
+            );
+        }
     }
 }
 
@@ -198,10 +1042,7 @@ fn generate_model(
     schema: &TableSchema,
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let domain_dir = Path::new(output_dir)
-        .join("domains")
-        .join(&schema.module_name)
-        .join("domain");
+    let domain_dir = module_dir(output_dir, schema).join("domain");
     fs::create_dir_all(&domain_dir)?;
 
     let tera = Tera::new("templates/**/*")?;
@@ -210,21 +1051,29 @@ fn generate_model(
     context.insert("table_name", &schema.table_name);
     context.insert("struct_name", &schema.struct_name);
 
+    // Decimal rounds digits beyond its precision, so flag columns declared wider
+    for col in &schema.columns {
+        if let Some(
+            ExactNumberInfo::Precision(precision)
+            | ExactNumberInfo::PrecisionAndScale(precision, _),
+        ) = decimal_info(col)
+            && *precision > DECIMAL_MAX_PRECISION
+            && !schema.decimal_as_f64
+        {
+            eprintln!(
+                "Warning: table `{}`: column `{}` is {} but rust_decimal::Decimal holds at \
+                 most {} digits",
+                schema.table_name, col.name, col.sql_type, DECIMAL_MAX_PRECISION
+            );
+        }
+    }
+
     let fields = schema
         .columns
         .iter()
         .map(|col| {
             let mut map = HashMap::new();
-            // Determine Rust type, using DateTime<Utc> for timestamps
-            let base_type = col.sql_type.to_lowercase();
-            let rust_type = if base_type == "timestamp"
-                || base_type == "timestamptz"
-                || base_type == "datetime"
-            {
-                "DateTime<Utc>".to_string()
-            } else {
-                map_sql_type(&col.sql_type).to_string()
-            };
+            let rust_type = column_type(schema, col);
 
             // Wrap in Option<> if the column is nullable
             let final_type = if col.is_nullable {
@@ -234,12 +1083,16 @@ fn generate_model(
             };
 
             map.insert("rust_type", final_type);
-            map.insert("name", col.name.clone());
+            map.insert("name", col.field_name.clone());
+            map.insert("rename", field_rename(col));
+            map.insert("comment", col.comment.clone().unwrap_or_default());
             map
         })
         .collect::<Vec<_>>();
 
     context.insert("fields", &fields);
+    context.insert("table_comment", &schema.comment.clone().unwrap_or_default());
+    insert_type_imports(&mut context, &schema.columns);
 
     // If any field uses DateTime<Utc>, ensure the template sees it
     context.insert("use_chrono", &true);
@@ -258,23 +1111,22 @@ pub fn generate_dto(
     let struct_name = &schema.struct_name;
 
     // Determine the path where the DTO file will be written
-    let dto_path = Path::new(output_dir)
-        .join("domains")
-        .join(&schema.module_name)
-        .join("dto");
+    let dto_path = module_dir(output_dir, schema).join("dto");
 
     fs::create_dir_all(&dto_path)?;
 
     let tera = Tera::new("templates/**/*")?;
     let mut context = Context::new();
+    insert_primary_key(&mut context, schema);
 
     // Insert the struct_name, module_name into context
     context.insert("struct_name", struct_name);
     context.insert("module_name", &schema.module_name);
+    context.insert("module_path", &module_path(schema));
+    insert_type_imports(&mut context, &schema.columns);
 
     // Retrieve rule-based field lists or fallback to empty vectors
     let create_skip = schema.create_special_fields.as_deref().unwrap_or(&[]);
-    let update_skip = schema.update_special_fields.as_deref().unwrap_or(&[]);
     let always_include = schema.always_include_in_dto.as_deref().unwrap_or(&[]);
 
     // Build 'fields' context: includes name, type, datetime and optional flags
@@ -283,17 +1135,7 @@ pub fn generate_dto(
         .iter()
         .map(|col| {
             let mut map = HashMap::new();
-            let sql_lower = col.sql_type.to_lowercase();
-
-            // Map SQL datetime types to DateTime<Utc>, others via map_sql_type
-            let base_type = if sql_lower == "timestamp"
-                || sql_lower == "timestamptz"
-                || sql_lower == "datetime"
-            {
-                "DateTime<Utc>".to_string()
-            } else {
-                map_sql_type(&col.sql_type).to_string()
-            };
+            let base_type = dto_type(schema, col);
 
             // Wrap in Option<> if the column is nullable
             let ty = if col.is_nullable {
@@ -302,19 +1144,40 @@ pub fn generate_dto(
                 base_type.clone()
             };
 
-            map.insert("name", col.name.clone());
+            map.insert("name", col.field_name.clone());
+            map.insert("rename", field_rename(col));
+            insert_comment(&mut map, col);
             map.insert("ty", ty);
             // Flag indicating whether this is a datetime column
-            let is_dt =
-                (sql_lower == "timestamp" || sql_lower == "timestamptz" || sql_lower == "datetime")
-                    .to_string();
+            let is_dt = is_datetime(col).to_string();
             map.insert("is_datetime", is_dt);
+            map.insert("is_naive_datetime", is_naive_datetime(col).to_string());
+            map.insert("is_decimal", is_decimal(schema, col).to_string());
+            map.insert("schema_type", schema_type(schema, col));
+            map.insert(
+                "pg_format",
+                pg_format(&column_type(schema, col))
+                    .map(|(module, _)| module.to_string())
+                    .unwrap_or_default(),
+            );
             // Flag indicating whether this field is optional
             map.insert("is_optional", col.is_nullable.to_string());
+            map.insert("default", col.default.clone().unwrap_or_default());
+            // Conversion of the model field, unwrapping typed JSON documents
+            let from = match (&col.json_type, col.is_nullable) {
+                (None, _) => format!("model.{}", col.field_name),
+                (Some(_), true) => format!("model.{}.map(|json| json.0)", col.field_name),
+                (Some(_), false) => format!("model.{}.0", col.field_name),
+            };
+            map.insert("from", from);
             map
         })
         .collect::<Vec<_>>();
     context.insert("fields", &fields);
+    context.insert(
+        "has_json_fields",
+        &schema.columns.iter().any(|col| col.json_type.is_some()),
+    );
 
     // Build 'create_fields' context: exclude rule-based skip fields
     let create_fields = schema
@@ -323,27 +1186,41 @@ pub fn generate_dto(
         .filter(|col| !create_skip.contains(&col.name))
         .map(|col| {
             let mut map = HashMap::new();
-            let sql_lower = col.sql_type.to_lowercase();
-            let base_type = if sql_lower == "timestamp"
-                || sql_lower == "timestamptz"
-                || sql_lower == "datetime"
-            {
-                "DateTime<Utc>".to_string()
-            } else {
-                map_sql_type(&col.sql_type).to_string()
-            };
-            let ty = if col.is_nullable {
+            let base_type = dto_type(schema, col);
+            // Columns with a DEFAULT may be omitted, letting the database fill them
+            let has_default = has_insert_default(schema, col);
+            let is_optional = col.is_nullable || has_default;
+            let ty = if is_optional {
                 format!("Option<{}>", base_type)
             } else {
                 base_type.clone()
             };
-            map.insert("name", col.name.clone());
+            map.insert("name", col.field_name.clone());
+            map.insert("rename", field_rename(col));
+            insert_comment(&mut map, col);
             map.insert("ty", ty);
-            let is_dt =
-                (sql_lower == "timestamp" || sql_lower == "timestamptz" || sql_lower == "datetime")
-                    .to_string();
+            let is_dt = is_datetime(col).to_string();
             map.insert("is_datetime", is_dt);
-            map.insert("is_optional", col.is_nullable.to_string());
+            map.insert("is_naive_datetime", is_naive_datetime(col).to_string());
+            map.insert("is_decimal", is_decimal(schema, col).to_string());
+            map.insert("schema_type", schema_type(schema, col));
+            map.insert(
+                "pg_format",
+                pg_format(&column_type(schema, col))
+                    .map(|(module, _)| module.to_string())
+                    .unwrap_or_default(),
+            );
+            map.insert("is_optional", is_optional.to_string());
+            map.insert("has_default", has_default.to_string());
+            map.insert("default", col.default.clone().unwrap_or_default());
+            // Decimals are documented as strings, the way they are serialized
+            let schema_default = match &col.default_literal {
+                // Array and JSON defaults such as `'{}'` are not the documented values
+                Some(_) if is_array(col) || is_json(col) => String::new(),
+                Some(literal) if is_decimal(schema, col) => format!("{:?}", literal),
+                literal => literal.clone().unwrap_or_default(),
+            };
+            map.insert("schema_default", schema_default);
             map
         })
         .collect::<Vec<_>>();
@@ -353,30 +1230,31 @@ pub fn generate_dto(
     let update_fields = schema
         .columns
         .iter()
-        .filter(|col| !update_skip.contains(&col.name))
+        .filter(|col| is_updatable(schema, col))
         .map(|col| {
             let mut map = HashMap::new();
-            let sql_lower = col.sql_type.to_lowercase();
-            let base_type = if sql_lower == "timestamp"
-                || sql_lower == "timestamptz"
-                || sql_lower == "datetime"
-            {
-                "DateTime<Utc>".to_string()
-            } else {
-                map_sql_type(&col.sql_type).to_string()
-            };
+            let base_type = dto_type(schema, col);
             // If always include, use base_type; else if not skip, use Option<>
             let ty = if always_include.contains(&col.name) {
                 base_type.clone()
             } else {
                 format!("Option<{}>", base_type)
             };
-            map.insert("name", col.name.clone());
+            map.insert("name", col.field_name.clone());
+            map.insert("rename", field_rename(col));
+            insert_comment(&mut map, col);
             map.insert("ty", ty);
-            let is_dt =
-                (sql_lower == "timestamp" || sql_lower == "timestamptz" || sql_lower == "datetime")
-                    .to_string();
+            let is_dt = is_datetime(col).to_string();
             map.insert("is_datetime", is_dt);
+            map.insert("is_naive_datetime", is_naive_datetime(col).to_string());
+            map.insert("is_decimal", is_decimal(schema, col).to_string());
+            map.insert("schema_type", schema_type(schema, col));
+            map.insert(
+                "pg_format",
+                pg_format(&column_type(schema, col))
+                    .map(|(module, _)| module.to_string())
+                    .unwrap_or_default(),
+            );
             let is_opt = (!always_include.contains(&col.name)).to_string();
             map.insert("is_optional", is_opt);
             map
@@ -386,7 +1264,7 @@ pub fn generate_dto(
 
     // Render the template and write to file
     let dto_code = tera.render("dto.tera", &context)?;
-    let dto_file_name = format!("{}_dto.rs", struct_name.to_lowercase());
+    let dto_file_name = format!("{}_dto.rs", schema.module_name);
 
     fs::write(dto_path.join(dto_file_name), dto_code)?;
 
@@ -398,18 +1276,20 @@ fn generate_repository(
     schema: &TableSchema,
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let domain_dir = Path::new(output_dir)
-        .join("domains")
-        .join(&schema.module_name)
-        .join("domain");
+    let domain_dir = module_dir(output_dir, schema).join("domain");
     fs::create_dir_all(&domain_dir)?;
 
     let tera = Tera::new("templates/**/*")?;
     let mut context = Context::new();
+    insert_primary_key(&mut context, schema);
+    insert_relations(&mut context, schema);
+    insert_type_imports(&mut context, signature_columns(schema));
+    insert_database(&mut context, schema.dialect);
 
     context.insert("struct_name", &schema.struct_name);
     context.insert("table_name", &schema.table_name);
     context.insert("module_name", &schema.module_name);
+    context.insert("module_path", &module_path(schema));
 
     let repository_code = tera.render("repository.tera", &context)?;
     fs::write(domain_dir.join("repository.rs"), repository_code)?;
@@ -422,18 +1302,20 @@ fn generate_service(
     schema: &TableSchema,
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let domain_dir = Path::new(output_dir)
-        .join("domains")
-        .join(&schema.module_name)
-        .join("domain");
+    let domain_dir = module_dir(output_dir, schema).join("domain");
     fs::create_dir_all(&domain_dir)?;
 
     let tera = Tera::new("templates/**/*")?;
     let mut context = Context::new();
+    insert_primary_key(&mut context, schema);
+    insert_relations(&mut context, schema);
+    insert_type_imports(&mut context, signature_columns(schema));
+    insert_database(&mut context, schema.dialect);
 
     context.insert("struct_name", &schema.struct_name);
     // Insert module_name instead of table_name for service template
     context.insert("module_name", &schema.module_name);
+    context.insert("module_path", &module_path(schema));
 
     let service_code = tera.render("service.tera", &context)?;
     fs::write(domain_dir.join("service.rs"), service_code)?;
@@ -446,18 +1328,25 @@ fn generate_routes(
     schema: &TableSchema,
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let base_dir = Path::new(output_dir)
-        .join("domains")
-        .join(&schema.module_name)
-        .join("api");
+    let base_dir = module_dir(output_dir, schema).join("api");
 
     fs::create_dir_all(&base_dir)?;
 
     let tera = Tera::new("templates/**/*")?;
     let mut context = Context::new();
+    insert_primary_key(&mut context, schema);
+    insert_relations(&mut context, schema);
 
     context.insert("struct_name", &schema.struct_name);
     context.insert("module_name", &schema.module_name);
+    context.insert("module_path", &module_path(schema));
+
+    // The table comment describes the OpenAPI tag, as a Rust string literal
+    let tag_description = match &schema.comment {
+        Some(comment) => format!("{:?}", comment),
+        None => format!("\"{} management endpoints\"", schema.struct_name),
+    };
+    context.insert("tag_description", &tag_description);
 
     let routes_code = tera.render("routes.tera", &context)?;
     fs::write(base_dir.join("routes.rs"), routes_code)?;
@@ -470,18 +1359,48 @@ fn generate_handlers(
     schema: &TableSchema,
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let base_dir = Path::new(output_dir)
-        .join("domains")
-        .join(&schema.module_name)
-        .join("api");
+    let base_dir = module_dir(output_dir, schema).join("api");
 
     fs::create_dir_all(&base_dir)?;
 
     let tera = Tera::new("templates/**/*")?;
     let mut context = Context::new();
+    insert_primary_key(&mut context, schema);
+    insert_relations(&mut context, schema);
+    insert_type_imports(&mut context, signature_columns(schema));
 
     context.insert("struct_name", &schema.struct_name);
     context.insert("module_name", &schema.module_name);
+    context.insert("module_path", &module_path(schema));
+
+    // Audit columns set to the authenticated user: `create_user_fields` and `update_user_fields`
+    // on create, `update_user_fields` on update, for the columns of the DTO
+    let create_skip = schema.create_special_fields.as_deref().unwrap_or(&[]);
+    let update_skip = schema.update_special_fields.as_deref().unwrap_or(&[]);
+    let always_include = schema.always_include_in_dto.as_deref().unwrap_or(&[]);
+    let create_users = user_fields(
+        schema,
+        schema
+            .create_user_fields
+            .iter()
+            .chain(&schema.update_user_fields)
+            .flatten()
+            .filter(|name| !create_skip.contains(name)),
+        |col| col.is_nullable || has_insert_default(schema, col),
+        "claims.sub.clone()",
+    );
+    let update_users = user_fields(
+        schema,
+        schema
+            .update_user_fields
+            .iter()
+            .flatten()
+            .filter(|name| !update_skip.contains(name)),
+        |col| !always_include.contains(&col.name),
+        "claims.sub.clone()",
+    );
+    context.insert("create_user_fields", &create_users);
+    context.insert("update_user_fields", &update_users);
 
     let handlers_code = tera.render("handlers.tera", &context)?;
     fs::write(base_dir.join("handlers.rs"), handlers_code)?;
@@ -494,18 +1413,20 @@ fn generate_impl_service(
     schema: &TableSchema,
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let base_dir = Path::new(output_dir)
-        .join("domains")
-        .join(&schema.module_name)
-        .join("infra");
+    let base_dir = module_dir(output_dir, schema).join("infra");
 
     fs::create_dir_all(&base_dir)?;
 
     let tera = Tera::new("templates/**/*")?;
     let mut context = Context::new();
+    insert_primary_key(&mut context, schema);
+    insert_relations(&mut context, schema);
+    insert_type_imports(&mut context, signature_columns(schema));
+    insert_database(&mut context, schema.dialect);
 
     context.insert("struct_name", &schema.struct_name);
     context.insert("module_name", &schema.module_name);
+    context.insert("module_path", &module_path(schema));
 
     let impl_service_code = tera.render("impl_service.tera", &context)?;
 
@@ -519,96 +1440,257 @@ fn generate_impl_repository(
     schema: &TableSchema,
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let base_dir = Path::new(output_dir)
-        .join("domains")
-        .join(&schema.module_name)
-        .join("infra");
+    let base_dir = module_dir(output_dir, schema).join("infra");
     fs::create_dir_all(&base_dir)?;
 
     let tera = Tera::new("templates/**/*")?;
     let mut context = Context::new();
+    insert_primary_key(&mut context, schema);
+    insert_relations(&mut context, schema);
+    insert_type_imports(&mut context, signature_columns(schema));
+    insert_database(&mut context, schema.dialect);
 
     // Insert basic context values
     context.insert("struct_name", &schema.struct_name);
     context.insert("module_name", &schema.module_name);
+    context.insert("module_path", &module_path(schema));
     context.insert("table_name", &schema.table_name);
+    context.insert("qualified_table_name", &schema.sql_name);
 
     // Prepare select_fields: list of column names
     let select_fields = schema
         .columns
         .iter()
-        .map(|col| col.name.clone())
+        .map(|col| select_field(schema, col))
         .collect::<Vec<_>>();
     context.insert("select_fields", &select_fields);
 
-    // Retrieve rule-based update skip list or fallback to empty slice
-    let update_skip = schema.update_special_fields.as_deref().unwrap_or(&[]);
+    // Retrieve rule-based field lists or fallback to empty slices
+    let create_skip = schema.create_special_fields.as_deref().unwrap_or(&[]);
+    let always_include = schema.always_include_in_dto.as_deref().unwrap_or(&[]);
+    let update_timestamps = schema.update_timestamp_fields.as_deref().unwrap_or(&[]);
 
-    // Prepare insert_fields: exclude id, created_at, and modified_at
+    // Prepare insert_fields: the Create DTO fields
     let insert_fields = schema
         .columns
         .iter()
-        .filter(|col| !(col.name == "id" || col.name == "created_at" || col.name == "modified_at"))
+        .filter(|col| !create_skip.contains(&col.name))
         .map(|col| {
             let mut map = HashMap::new();
-            map.insert("name", col.name.clone());
+            map.insert("name", col.field_name.clone());
+            map.insert("column", col.sql_name.clone());
             // Flag indicating datetime columns for create binding
-            let is_dt = (col.sql_type.to_lowercase() == "timestamp"
-                || col.sql_type.to_lowercase() == "timestamptz"
-                || col.sql_type.to_lowercase() == "datetime")
-                .to_string();
+            let is_dt = is_datetime(col).to_string();
             map.insert("is_datetime", is_dt);
+            map.insert("cast", sql_cast(schema, col).to_string());
+            // Defaulted columns are left out of the INSERT when the payload omits them
+            map.insert(
+                "omit_when_none",
+                has_insert_default(schema, col).to_string(),
+            );
+            map.insert(
+                "bind",
+                dto_bind(col, &format!("payload.{}", col.field_name), col.is_nullable),
+            );
+            map.insert("bind_value", dto_bind(col, "value", false));
             map
         })
         .collect::<Vec<_>>();
     context.insert("insert_fields", &insert_fields);
 
-    // Prepare update_fields: filter out any column listed in update_skip
+    // Prepare update_fields: the Update DTO fields, optional unless always included
     let update_fields = schema
         .columns
         .iter()
-        .filter(|col| !update_skip.contains(&col.name))
+        .filter(|col| is_updatable(schema, col))
         .map(|col| {
             let mut map = HashMap::new();
-            let is_dt = (col.sql_type.to_lowercase() == "timestamp"
-                || col.sql_type.to_lowercase() == "timestamptz"
-                || col.sql_type.to_lowercase() == "datetime")
-                .to_string();
-            // For update, columns in update_special_fields are skipped entirely
-            // For modified_by, always include as non-Option; for others, wrap in Option
-            let ty = if col.name == "modified_by" {
-                // Use base type; Tera will detect datetime if needed
-                if is_dt == "true" {
-                    "DateTime<Utc>".to_string()
-                } else {
-                    map_sql_type(&col.sql_type).to_string()
-                }
+            let is_dt = is_datetime(col).to_string();
+            let base_type = column_type(schema, col);
+            let is_optional = !always_include.contains(&col.name);
+            let ty = if is_optional {
+                format!("Option<{}>", base_type)
             } else {
-                // Option<...>
-                if is_dt == "true" {
-                    format!("Option<DateTime<Utc>>")
-                } else {
-                    format!("Option<{}>", map_sql_type(&col.sql_type))
-                }
+                base_type
             };
-            map.insert("name", col.name.clone());
+            map.insert("name", col.field_name.clone());
+            map.insert("column", col.sql_name.clone());
             map.insert("is_datetime", is_dt);
-            map.insert("is_optional", (col.name != "modified_by").to_string());
+            map.insert("is_optional", is_optional.to_string());
             map.insert("ty", ty);
+            map.insert("cast", sql_cast(schema, col).to_string());
+            map.insert(
+                "bind",
+                dto_bind(col, &format!("payload.{}.clone()", col.field_name), false),
+            );
+            map.insert("bind_value", dto_bind(col, "value", false));
             map
         })
         .collect::<Vec<_>>();
     context.insert("update_fields", &update_fields);
 
+    // Prepare timestamp_fields: columns set to the current time on update
+    let timestamp_fields = update_timestamps
+        .iter()
+        .filter_map(|name| schema.columns.iter().find(|col| &col.name == name))
+        .map(|col| col.sql_name.clone())
+        .collect::<Vec<_>>();
+    context.insert("timestamp_fields", &timestamp_fields);
+
+    // Condition under which the update assigns nothing, when every assignment is optional
+    let update_unchanged_when = if timestamp_fields.is_empty()
+        && update_fields
+            .iter()
+            .all(|field| field["is_optional"] == "true")
+    {
+        update_fields
+            .iter()
+            .map(|field| format!("payload.{}.is_none()", field["name"]))
+            .collect::<Vec<_>>()
+            .join(" && ")
+    } else {
+        String::new()
+    };
+    context.insert("update_unchanged_when", &update_unchanged_when);
+
     // Render and write file
     let impl_repository_code = tera.render("impl_repository.tera", &context)?;
     fs::write(base_dir.join("impl_repository.rs"), impl_repository_code)?;
     Ok(())
 }
 
+/// Returns the table and column referenced by a column through a single-column foreign key,
+/// when the referenced table is another table of the schema.
+fn referenced_column<'a>(
+    schema: &TableSchema,
+    schemas: &'a [TableSchema],
+    col: &TableColumn,
+) -> Option<(&'a TableSchema, &'a TableColumn)> {
+    schema.foreign_keys.iter().find_map(|fk| {
+        let ([column], [referenced_column]) =
+            (fk.columns.as_slice(), fk.referenced_columns.as_slice())
+        else {
+            return None;
+        };
+        if column != &col.name {
+            return None;
+        }
+        let parent = schemas.iter().find(|parent| {
+            parent.is_named(&fk.referenced_table)
+                && parent.qualified_name() != schema.qualified_name()
+        })?;
+        let parent_col = parent
+            .columns
+            .iter()
+            .find(|parent_col| &parent_col.name == referenced_column)?;
+        Some((parent, parent_col))
+    })
+}
+
+/// Returns the Create DTO fields of a table with the value sent by the tests, and the tables
+/// whose rows the tests create first so the foreign keys hold, held in variables named after
+/// their module. Foreign keys to the tables in `creating`, whose rows are being created
+/// already, keep their default. Defaulted fields are sent explicitly so the response echoes
+/// them back, and keys get a fresh value so repeated creates succeed.
+fn test_create_values<'a>(
+    schema: &TableSchema,
+    schemas: &'a [TableSchema],
+    creating: &[String],
+) -> (Vec<HashMap<&'static str, String>>, Vec<&'a TableSchema>) {
+    let create_skip = schema.create_special_fields.as_deref().unwrap_or(&[]);
+
+    let mut fields = Vec::new();
+    let mut parents = Vec::<&TableSchema>::new();
+    for col in schema
+        .columns
+        .iter()
+        .filter(|col| !create_skip.contains(&col.name))
+    {
+        let is_key = schema.primary_key.contains(&col.name);
+        let is_foreign_key = schema
+            .foreign_keys
+            .iter()
+            .any(|fk| fk.columns.contains(&col.name));
+        let has_default = has_insert_default(schema, col);
+        let is_optional = col.is_nullable || has_default;
+        let ty = column_type(schema, col);
+        let reference = referenced_column(schema, schemas, col)
+            .filter(|(parent, _)| !creating.contains(&parent.qualified_name()));
+
+        let value = if let Some((parent, parent_col)) = reference {
+            if !parents
+                .iter()
+                .any(|p| p.qualified_name() == parent.qualified_name())
+            {
+                parents.push(parent);
+            }
+            let mut value = format!("{}.{}.clone()", parent.module_name, parent_col.field_name);
+            if parent_col.is_nullable {
+                value.push_str(".unwrap()");
+            }
+            if is_optional {
+                format!("Some({})", value)
+            } else {
+                value
+            }
+        } else if is_foreign_key && is_optional {
+            "Default::default()".to_string()
+        } else if let Some(key) = fresh_key_value(&ty).filter(|_| is_key) {
+            key
+        } else if has_default {
+            format!("Some({})", sample_value(&ty))
+        } else if col.is_nullable {
+            "Default::default()".to_string()
+        } else {
+            sample_value(&ty)
+        };
+
+        let mut map = HashMap::new();
+        map.insert("name", col.field_name.clone());
+        map.insert("value", value);
+        fields.push(map);
+    }
+
+    (fields, parents)
+}
+
+/// Appends to `fixtures` the tables whose rows the tests of a table create first, each after
+/// the tables it references in turn, with its Create DTO values and the modules of those
+/// tables. `creating` lists the tables on the way, whose rows are being created already.
+fn insert_test_fixtures(
+    schema: &TableSchema,
+    schemas: &[TableSchema],
+    creating: &mut Vec<String>,
+    fixtures: &mut Vec<serde_json::Value>,
+) {
+    let (_, parents) = test_create_values(schema, schemas, creating);
+    for parent in parents {
+        if fixtures
+            .iter()
+            .any(|fixture| fixture["module_name"] == parent.module_name)
+        {
+            continue;
+        }
+
+        creating.push(parent.qualified_name());
+        insert_test_fixtures(parent, schemas, creating, fixtures);
+        let (fields, grandparents) = test_create_values(parent, schemas, creating);
+        creating.pop();
+
+        fixtures.push(serde_json::json!({
+            "module_name": parent.module_name,
+            "module_path": module_path(parent),
+            "struct_name": parent.struct_name,
+            "fields": fields,
+            "parents": grandparents.iter().map(|p| &p.module_name).collect::<Vec<_>>(),
+        }));
+    }
+}
+
 /// Generates the `test_{module_name}_routes.rs` file under the tests directory.
 fn generate_tests(
     schema: &TableSchema,
+    schemas: &[TableSchema],
     output_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let tests_dir = Path::new(output_dir).parent().unwrap().join("tests");
@@ -616,24 +1698,122 @@ fn generate_tests(
 
     let tera = Tera::new("templates/**/*")?;
     let mut context = Context::new();
+    insert_primary_key(&mut context, schema);
+    insert_relations(&mut context, schema);
     context.insert("struct_name", &schema.struct_name);
     context.insert("module_name", &schema.module_name);
+    context.insert("module_path", &module_path(schema));
 
-    // Prepare create_fields: exclude id, created_at, modified_at
-    let create_fields = schema
+    // Retrieve rule-based field lists or fallback to empty slices
+    let always_include = schema.always_include_in_dto.as_deref().unwrap_or(&[]);
+
+    // Columns the handlers set to the authenticated user are expected to hold the test user's id
+    let create_users = user_fields(
+        schema,
+        schema
+            .create_user_fields
+            .iter()
+            .chain(&schema.update_user_fields)
+            .flatten(),
+        |col| col.is_nullable,
+        "TEST_USER_ID.to_string()",
+    );
+    let update_users = user_fields(
+        schema,
+        schema.update_user_fields.iter().flatten(),
+        |col| col.is_nullable,
+        "TEST_USER_ID.to_string()",
+    );
+    let user_value = |users: &[HashMap<&str, String>], col: &TableColumn| {
+        users
+            .iter()
+            .find(|field| field["name"] == col.field_name)
+            .map(|field| field["value"].clone())
+    };
+
+    // Prepare create_fields: the Create DTO fields with the value sent by the tests and the
+    // expression the response is compared against, and the fixtures creating the rows they
+    // reference
+    let mut creating = vec![schema.qualified_name()];
+    let (mut create_fields, parents) = test_create_values(schema, schemas, &creating);
+    let mut fixtures = Vec::new();
+    insert_test_fixtures(schema, schemas, &mut creating, &mut fixtures);
+    for field in &mut create_fields {
+        let col = schema
+            .columns
+            .iter()
+            .find(|col| col.field_name == field["name"])
+            .unwrap();
+        let expected = if let Some(user_value) = user_value(&create_users, col) {
+            user_value
+        } else if has_insert_default(schema, col) && !col.is_nullable {
+            format!("payload.{}.unwrap()", col.field_name)
+        } else {
+            format!("payload.{}", col.field_name)
+        };
+        field.insert("expected", expected);
+    }
+    context.insert(
+        "parents",
+        &parents.iter().map(|p| &p.module_name).collect::<Vec<_>>(),
+    );
+    context.insert("fixtures", &fixtures);
+
+    // Fresh and missing keys are built from random UUIDs
+    let fixture_values = fixtures
+        .iter()
+        .flat_map(|fixture| fixture["fields"].as_array().cloned().unwrap_or_default())
+        .map(|field| field["value"].as_str().unwrap_or_default().to_string());
+    let missing_values = schema
         .columns
         .iter()
-        .filter(|col| !(col.name == "id" || col.name == "created_at" || col.name == "modified_at"))
-        .map(|col| col.name.clone())
-        .collect::<Vec<_>>();
+        .filter(|col| schema.primary_key.contains(&col.name))
+        .map(|col| missing_key_value(&column_type(schema, col)));
+    let uses_uuid = create_fields
+        .iter()
+        .map(|field| field["value"].clone())
+        .chain(fixture_values)
+        .chain(missing_values)
+        .any(|value| value.contains("Uuid::"));
+    context.insert("uses_uuid", &uses_uuid);
     context.insert("create_fields", &create_fields);
 
-    // Prepare update_fields: exclude id, created_at, created_by? (Keep modified_by required)
+    // Prepare update_fields: the Update DTO fields, optional unless always included, with the
+    // value sent by the tests and the expression the response is compared against. Foreign keys
+    // keep the entity's reference, and the columns set to the authenticated user are expected
+    // to hold the test user's id.
     let update_fields = schema
         .columns
         .iter()
-        .filter(|col| !(col.name == "id" || col.name == "created_at"))
-        .map(|col| col.name.clone())
+        .filter(|col| is_updatable(schema, col))
+        .map(|col| {
+            let is_optional = !always_include.contains(&col.name);
+            let is_foreign_key = schema
+                .foreign_keys
+                .iter()
+                .any(|fk| fk.columns.contains(&col.name));
+            let value = match (is_foreign_key, is_optional) {
+                (true, true) if !col.is_nullable => {
+                    format!("Some(entity.{}.clone())", col.field_name)
+                }
+                (true, _) | (false, false) => format!("entity.{}.clone()", col.field_name),
+                (false, true) => format!("Some({})", sample_value(&column_type(schema, col))),
+            };
+            let expected = if let Some(user_value) = user_value(&update_users, col) {
+                user_value
+            } else if is_foreign_key {
+                format!("entity.{}", col.field_name)
+            } else if is_optional && !col.is_nullable {
+                format!("payload.{}.unwrap()", col.field_name)
+            } else {
+                format!("payload.{}", col.field_name)
+            };
+            let mut map = HashMap::new();
+            map.insert("name", col.field_name.clone());
+            map.insert("value", value);
+            map.insert("expected", expected);
+            map
+        })
         .collect::<Vec<_>>();
     context.insert("update_fields", &update_fields);
 
@@ -1,103 +1,1382 @@
-use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::ast::{
+    AlterColumnOperation, AlterTableOperation, ArrayElemTypeDef, CharacterLength, ColumnDef,
+    ColumnOption, ColumnOptionDef, CommentDef, CommentObject, CreateTable, DataType, Expr,
+    GeneratedAs, Ident, ObjectName, ObjectNamePart, ObjectType, Statement, TableConstraint,
+    UnaryOperator, UserDefinedTypeRepresentation, Value,
+};
+use sqlparser::dialect::{MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::keywords::Keyword;
 use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
 use std::fs;
+use std::path::{Path, PathBuf};
 
-use crate::generator::to_pascal_case;
+use crate::generator::{column_type, to_pascal_case};
+use crate::rules::{Rules, TYPE_OVERRIDES_KEY, TypeOverride};
+
+/// SQL dialect of the schema, selecting the parser and the sqlx database the generated
+/// repositories target. Set by `--dialect` or the rules' `dialect`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+pub enum SqlDialect {
+    #[default]
+    #[value(name = "postgres", alias = "postgresql")]
+    #[serde(rename = "postgres", alias = "postgresql")]
+    Postgres,
+    #[value(name = "mysql")]
+    #[serde(rename = "mysql")]
+    MySql,
+    #[value(name = "sqlite")]
+    #[serde(rename = "sqlite")]
+    Sqlite,
+}
+
+impl SqlDialect {
+    /// Name of the dialect as passed to `--dialect` and seen by the templates.
+    pub fn name(self) -> &'static str {
+        match self {
+            SqlDialect::Postgres => "postgres",
+            SqlDialect::MySql => "mysql",
+            SqlDialect::Sqlite => "sqlite",
+        }
+    }
+
+    /// sqlx connection pool type, e.g. `PgPool`.
+    pub fn pool_type(self) -> &'static str {
+        match self {
+            SqlDialect::Postgres => "PgPool",
+            SqlDialect::MySql => "MySqlPool",
+            SqlDialect::Sqlite => "SqlitePool",
+        }
+    }
+
+    /// sqlx database type, e.g. `Postgres` in `Transaction<'_, Postgres>`.
+    pub fn database_type(self) -> &'static str {
+        match self {
+            SqlDialect::Postgres => "Postgres",
+            SqlDialect::MySql => "MySql",
+            SqlDialect::Sqlite => "Sqlite",
+        }
+    }
+
+    /// Placeholder of the n-th (1-based) bind parameter: `$n` for PostgreSQL, `?` otherwise.
+    pub fn placeholder(self, n: usize) -> String {
+        match self {
+            SqlDialect::Postgres => format!("${}", n),
+            SqlDialect::MySql | SqlDialect::Sqlite => "?".to_string(),
+        }
+    }
+
+    /// SQL expression of the current time, set by updates on the `update_timestamp_fields`.
+    pub fn now(self) -> &'static str {
+        match self {
+            SqlDialect::Postgres | SqlDialect::MySql => "NOW()",
+            SqlDialect::Sqlite => "CURRENT_TIMESTAMP",
+        }
+    }
+}
 
 pub struct TableColumn {
     pub name: String,
+    /// Name of the column in SQL queries, quoted as in the schema, e.g. `"createdAt"`.
+    pub sql_name: String,
+    /// Rust field of the column, e.g. `created_at` for `"createdAt"` or `r#type` for `type`.
+    /// Set by the rules' `field_names`.
+    pub field_name: String,
     pub sql_type: String,
+    /// Parsed SQL type, carrying details such as the precision and scale of `NUMERIC(12,2)`.
+    pub data_type: DataType,
     pub is_nullable: bool,
+    /// Whether the database fills the column on insert (serial, identity or default).
+    pub is_generated: bool,
+    /// `DEFAULT` expression as written in the SQL, e.g. `'pending'` or `CURRENT_TIMESTAMP`.
+    pub default: Option<String>,
+    /// The default as a Rust literal, when it is a constant, e.g. `"pending"`.
+    pub default_literal: Option<String>,
+    /// Rust type set for the column by the rules' `type_overrides`.
+    pub type_override: Option<TypeOverride>,
+    /// Rust type of the JSON document, set by the rules' `json_types`.
+    pub json_type: Option<String>,
+    /// Enum type of the column, when its type was declared with `CREATE TYPE ... AS ENUM`.
+    pub enum_type: Option<EnumType>,
+    /// `COMMENT ON COLUMN` text, or MySQL's inline `COMMENT '...'`.
+    pub comment: Option<String>,
+}
+
+/// A PostgreSQL enum declared with `CREATE TYPE ... AS ENUM`.
+#[derive(Clone)]
+pub struct EnumType {
+    /// Type name as declared in the SQL, e.g. `todo_status`.
+    pub name: String,
+    /// Rust enum name, e.g. `TodoStatus`.
+    pub type_name: String,
+    pub variants: Vec<EnumVariant>,
+    /// serde and sqlx `rename_all` style turning every variant name into its label, if any.
+    pub rename_all: Option<&'static str>,
+}
+
+/// A label of an enum type and the Rust variant generated for it.
+#[derive(Clone)]
+pub struct EnumVariant {
+    /// Label as declared in the SQL, e.g. `in_progress`.
+    pub label: String,
+    /// Rust variant name, e.g. `InProgress`.
+    pub name: String,
+}
+
+/// A `REFERENCES` clause or `FOREIGN KEY` constraint of a table.
+pub struct ForeignKey {
+    pub columns: Vec<String>,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+    /// Module name of the referenced table, resolved like the table's own.
+    pub referenced_module: String,
+    /// Struct name of the referenced table, resolved like the table's own.
+    pub referenced_struct: String,
 }
 
 pub struct TableSchema {
+    /// Table name without its schema, e.g. `todos` for `app.todos`.
     pub table_name: String,
+    /// Schema qualifying the table name in the SQL, e.g. `app` for `app.todos`.
+    pub schema_name: Option<String>,
+    /// Table name in SQL queries, qualified and quoted as in the schema, e.g. `app."Todos"`.
+    pub sql_name: String,
     pub columns: Vec<TableColumn>,
+    /// Columns forming the primary key, from column or table constraints, or `id` when the
+    /// table declares none.
+    pub primary_key: Vec<String>,
+    /// How the create handler obtains the key of a new row: `payload` (sent in the Create
+    /// DTO), `database` (generated by the database), `uuid` or `uuid_string` (generated by the
+    /// handler).
+    pub pk_strategy: &'static str,
+    pub foreign_keys: Vec<ForeignKey>,
     pub module_name: String,
+    /// Module grouping the table's module, named after its schema when the rules set
+    /// `group_by_schema`.
+    pub schema_module: Option<String>,
     pub struct_name: String,
     pub create_special_fields: Option<Vec<String>>,
     pub update_special_fields: Option<Vec<String>>,
     pub always_include_in_dto: Option<Vec<String>>,
+    pub update_timestamp_fields: Option<Vec<String>>,
+    /// Columns set to the authenticated user by the create handler.
+    pub create_user_fields: Option<Vec<String>>,
+    /// Columns set to the authenticated user by the create and update handlers.
+    pub update_user_fields: Option<Vec<String>>,
+    /// Whether NUMERIC/DECIMAL columns map to `f64` instead of `rust_decimal::Decimal`.
+    pub decimal_as_f64: bool,
+    /// Dialect the table was parsed with.
+    pub dialect: SqlDialect,
+    /// `COMMENT ON TABLE` text, or MySQL's `COMMENT = '...'` table option.
+    pub comment: Option<String>,
 }
 
+impl TableSchema {
+    /// Returns the table name as written in SQL queries, qualified by its schema if any.
+    pub fn qualified_name(&self) -> String {
+        match &self.schema_name {
+            Some(schema_name) => format!("{}.{}", schema_name, self.table_name),
+            None => self.table_name.clone(),
+        }
+    }
+
+    /// Returns whether a name from the rules or a `REFERENCES` clause designates the table,
+    /// either qualified (`app.todos`) or not (`todos`).
+    pub fn is_named(&self, name: &str) -> bool {
+        name == self.table_name || name == self.qualified_name()
+    }
+}
+
+/// Parses every `CREATE TABLE` statement of the schema into a table schema, replaying the
+/// `ALTER TABLE`, `COMMENT ON` and `DROP TABLE` statements that follow it, and applies the per-table
+/// generation rules to each of them. The schema is a SQL file or a directory of migrations.
 pub fn parse_sql_file(
     path: &str,
-    rules: Option<&serde_json::Value>,
-) -> Result<TableSchema, Box<dyn std::error::Error>> {
-    let sql = fs::read_to_string(path)?;
-    let dialect = PostgreSqlDialect {};
-    let statements = Parser::parse_sql(&dialect, &sql)?;
+    rules: Option<&Rules>,
+    dialect: SqlDialect,
+) -> Result<Vec<TableSchema>, Box<dyn std::error::Error>> {
+    let mut statements = Vec::new();
+    for file in schema_files(Path::new(path))? {
+        let sql = fs::read_to_string(&file)
+            .map_err(|err| format!("{}: failed to read SQL file: {}", file.display(), err))?;
+        let parsed = match dialect {
+            SqlDialect::Postgres => Parser::parse_sql(&PostgreSqlDialect {}, &sql),
+            SqlDialect::MySql => Parser::parse_sql(&MySqlDialect {}, &sql),
+            SqlDialect::Sqlite => Parser::parse_sql(&SQLiteDialect {}, &sql),
+        };
+        statements.extend(parsed.map_err(|err| format!("{}: {}", file.display(), err))?);
+    }
 
+    // Enum types may be referenced by tables declared before them
+    let enums = statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::CreateType {
+                name,
+                representation: UserDefinedTypeRepresentation::Enum { labels },
+            } => Some(enum_type(
+                object_name(name),
+                labels.iter().map(|label| label.value.clone()).collect(),
+            )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut tables = Vec::new();
+
+    // Replay each ALTER TABLE and COMMENT ON on the CREATE TABLE it targets, so the tables end
+    // in their final shape
+    let mut create_tables = Vec::new();
     for stmt in statements {
-        if let sqlparser::ast::Statement::CreateTable(create_table) = stmt {
-            let table_name = create_table.name.to_string();
-
-            let mut create_special_fields = None;
-            let mut update_special_fields = None;
-            let mut always_include_in_dto = None;
-
-            if let Some(rules) = rules {
-                if let Some(rule) = rules.get(&table_name) {
-                    if let Some(fields) =
-                        rule.get("create_special_fields").and_then(|v| v.as_array())
-                    {
-                        create_special_fields = Some(
-                            fields
-                                .iter()
-                                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                                .collect(),
-                        );
-                    }
-                    if let Some(fields) =
-                        rule.get("update_special_fields").and_then(|v| v.as_array())
-                    {
-                        update_special_fields = Some(
-                            fields
-                                .iter()
-                                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                                .collect(),
-                        );
-                    }
-                    if let Some(always) =
-                        rule.get("always_include_in_dto").and_then(|v| v.as_array())
-                    {
-                        always_include_in_dto = Some(
-                            always
-                                .iter()
-                                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                                .collect(),
-                        );
-                    }
+        match stmt {
+            Statement::CreateTable(create_table) => create_tables.push(create_table),
+            Statement::AlterTable {
+                name, operations, ..
+            } => alter_table(&mut create_tables, &name, operations)?,
+            Statement::Comment {
+                object_type,
+                object_name,
+                comment,
+                ..
+            } => comment_on(&mut create_tables, object_type, &object_name, comment)?,
+            Statement::Drop {
+                object_type: ObjectType::Table,
+                names,
+                ..
+            } => create_tables.retain(|table: &CreateTable| {
+                !names
+                    .iter()
+                    .any(|name| object_name(name) == object_name(&table.name))
+            }),
+            _ => {}
+        }
+    }
+
+    for create_table in create_tables {
+        let (schema_name, table_name) = split_object_name(&create_table.name);
+
+        // Collect column-level and table-level PRIMARY KEY constraints
+        let mut primary_key = create_table
+            .columns
+            .iter()
+            .filter(|col| {
+                col.options.iter().any(|opt| {
+                    matches!(
+                        opt.option,
+                        ColumnOption::Unique {
+                            is_primary: true,
+                            ..
+                        }
+                    )
+                })
+            })
+            .map(|col| col.name.value.clone())
+            .collect::<Vec<_>>();
+        for constraint in &create_table.constraints {
+            if let TableConstraint::PrimaryKey { columns, .. } = constraint {
+                primary_key.extend(columns.iter().map(|col| col.value.clone()));
+            }
+        }
+
+        // Collect column-level REFERENCES clauses and table-level FOREIGN KEY constraints
+        let mut foreign_keys = Vec::new();
+        for col in &create_table.columns {
+            for opt in &col.options {
+                if let ColumnOption::ForeignKey {
+                    foreign_table,
+                    referred_columns,
+                    ..
+                } = &opt.option
+                {
+                    foreign_keys.push(foreign_key(
+                        vec![col.name.value.clone()],
+                        object_name(foreign_table),
+                        referred_columns,
+                    ));
                 }
             }
+        }
+        for constraint in &create_table.constraints {
+            if let TableConstraint::ForeignKey {
+                columns,
+                foreign_table,
+                referred_columns,
+                ..
+            } = constraint
+            {
+                foreign_keys.push(foreign_key(
+                    columns.iter().map(|col| col.value.clone()).collect(),
+                    object_name(foreign_table),
+                    referred_columns,
+                ));
+            }
+        }
 
-            let parsed_columns = create_table
+        // In SQLite, a lone `INTEGER PRIMARY KEY` column aliases the rowid, which is
+        // assigned on insert when omitted
+        let rowid_alias = match primary_key.as_slice() {
+            [key] if dialect == SqlDialect::Sqlite => create_table
                 .columns
                 .iter()
-                .map(|col| {
-                    let is_nullable = !col
+                .find(|col| &col.name.value == key)
+                .filter(|col| col.data_type.to_string().eq_ignore_ascii_case("INTEGER"))
+                .map(|col| col.name.value.clone()),
+            _ => None,
+        };
+
+        let parsed_columns = create_table
+            .columns
+            .iter()
+            .map(|col| {
+                let name = col.name.value.clone();
+                // Primary key columns are implicitly NOT NULL
+                let is_nullable = !primary_key.contains(&name)
+                    && !col
                         .options
                         .iter()
-                        .any(|opt| matches!(opt.option, sqlparser::ast::ColumnOption::NotNull));
-                    TableColumn {
-                        name: col.name.value.clone(),
-                        sql_type: col.data_type.to_string(),
-                        is_nullable,
+                        .any(|opt| matches!(opt.option, ColumnOption::NotNull));
+                let sql_type = col.data_type.to_string();
+                let is_generated = is_serial(&col.data_type)
+                    || rowid_alias.as_ref() == Some(&name)
+                    || col.options.iter().any(|opt| {
+                        matches!(
+                            opt.option,
+                            ColumnOption::Default(_)
+                                | ColumnOption::Identity(_)
+                                | ColumnOption::Generated { .. }
+                        ) || is_auto_increment(&opt.option)
+                    });
+                let default_expr = col.options.iter().find_map(|opt| match &opt.option {
+                    ColumnOption::Default(expr) => Some(expr),
+                    _ => None,
+                });
+                TableColumn {
+                    sql_name: col.name.to_string(),
+                    field_name: rust_field_name(&name),
+                    name,
+                    sql_type,
+                    data_type: col.data_type.clone(),
+                    is_nullable,
+                    is_generated,
+                    default: default_expr.map(|expr| expr.to_string()),
+                    default_literal: default_expr.and_then(literal_value),
+                    type_override: None,
+                    json_type: None,
+                    enum_type: find_enum(&enums, &col.data_type).cloned(),
+                    comment: col.options.iter().find_map(|opt| match &opt.option {
+                        ColumnOption::Comment(comment) => comment_text(comment),
+                        _ => None,
+                    }),
+                }
+            })
+            .collect();
+
+        tables.push(TableSchema {
+            table_name: table_name.clone(),
+            schema_name,
+            sql_name: create_table.name.to_string(),
+            columns: parsed_columns,
+            primary_key,
+            pk_strategy: "payload",
+            foreign_keys,
+            module_name: rust_module_name(&table_name),
+            schema_module: None,
+            struct_name: to_pascal_case(&table_name),
+            create_special_fields: None,
+            update_special_fields: None,
+            always_include_in_dto: None,
+            update_timestamp_fields: None,
+            create_user_fields: None,
+            update_user_fields: None,
+            decimal_as_f64: dialect == SqlDialect::Sqlite,
+            dialect,
+            comment: create_table
+                .comment
+                .as_ref()
+                .and_then(|comment| match comment {
+                    CommentDef::WithEq(comment)
+                    | CommentDef::WithoutEq(comment)
+                    | CommentDef::AfterColumnDefsWithoutEq(comment) => comment_text(comment),
+                }),
+        });
+    }
+
+    if tables.is_empty() {
+        return Err("No valid CREATE TABLE statement found".into());
+    }
+
+    if let Some(rules) = rules {
+        apply_rules(&mut tables, rules)?;
+    }
+    check_module_names(&tables)?;
+    for table in tables.iter_mut() {
+        check_field_names(table)?;
+        check_user_fields(table)?;
+        check_primary_key(table)?;
+        check_array_dimensions(table)?;
+    }
+    resolve_foreign_keys(&mut tables);
+
+    Ok(tables)
+}
+
+/// Returns the SQL files making up the schema: the file itself, or the migrations of a
+/// directory in filename order (`.sql` files, skipping the `.down.sql` reverts of
+/// reversible migrations).
+fn schema_files(path: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)
+        .map_err(|err| format!("{}: failed to read migrations: {}", path.display(), err))?
+    {
+        let file = entry?.path();
+        let name = file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        if file.is_file() && name.ends_with(".sql") && !name.ends_with(".down.sql") {
+            files.push(file);
+        }
+    }
+    if files.is_empty() {
+        return Err(format!("{}: no .sql migration found", path.display()).into());
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+/// Replays the operations of an `ALTER TABLE` statement on the `CREATE TABLE` it targets:
+/// adding, dropping, renaming and redefining columns, changing their nullability, default and
+/// type, adding and dropping constraints, and renaming the table. Other operations are
+/// reported and ignored.
+fn alter_table(
+    create_tables: &mut [CreateTable],
+    name: &ObjectName,
+    operations: Vec<AlterTableOperation>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let table_name = object_name(name);
+    let Some(table) = create_tables
+        .iter_mut()
+        .find(|table| object_name(&table.name) == table_name)
+    else {
+        eprintln!(
+            "Warning: ALTER TABLE `{}` does not match any preceding CREATE TABLE statement",
+            table_name
+        );
+        return Ok(());
+    };
+
+    for operation in operations {
+        match operation {
+            AlterTableOperation::AddColumn {
+                if_not_exists,
+                column_def,
+                ..
+            } => {
+                if find_column(table, &column_def.name).is_some() {
+                    if if_not_exists {
+                        continue;
                     }
+                    return Err(format!(
+                        "ALTER TABLE `{}`: column `{}` already exists",
+                        table_name, column_def.name.value
+                    )
+                    .into());
+                }
+                table.columns.push(column_def);
+            }
+            AlterTableOperation::DropColumn {
+                column_name,
+                if_exists,
+                ..
+            } => {
+                let Some(index) = find_column(table, &column_name) else {
+                    if if_exists {
+                        continue;
+                    }
+                    return Err(missing_column(&table_name, &column_name));
+                };
+                table.columns.remove(index);
+                // Constraints on the dropped column are dropped with it
+                table.constraints.retain_mut(|constraint| {
+                    constraint_columns(constraint).is_none_or(|columns| {
+                        !columns.iter().any(|col| col.value == column_name.value)
+                    })
+                });
+            }
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                let index = find_column(table, &old_column_name)
+                    .ok_or_else(|| missing_column(&table_name, &old_column_name))?;
+                table.columns[index].name = new_column_name.clone();
+                for constraint in &mut table.constraints {
+                    for col in constraint_columns(constraint).into_iter().flatten() {
+                        if col.value == old_column_name.value {
+                            *col = new_column_name.clone();
+                        }
+                    }
+                }
+            }
+            AlterTableOperation::AlterColumn { column_name, op } => {
+                let index = find_column(table, &column_name)
+                    .ok_or_else(|| missing_column(&table_name, &column_name))?;
+                let column = &mut table.columns[index];
+                match op {
+                    AlterColumnOperation::SetNotNull => {
+                        column
+                            .options
+                            .retain(|opt| !matches!(opt.option, ColumnOption::NotNull));
+                        column.options.push(column_option(ColumnOption::NotNull));
+                    }
+                    AlterColumnOperation::DropNotNull => column
+                        .options
+                        .retain(|opt| !matches!(opt.option, ColumnOption::NotNull)),
+                    AlterColumnOperation::SetDefault { value } => {
+                        column
+                            .options
+                            .retain(|opt| !matches!(opt.option, ColumnOption::Default(_)));
+                        column
+                            .options
+                            .push(column_option(ColumnOption::Default(value)));
+                    }
+                    AlterColumnOperation::DropDefault => column
+                        .options
+                        .retain(|opt| !matches!(opt.option, ColumnOption::Default(_))),
+                    AlterColumnOperation::SetDataType { data_type, .. } => {
+                        column.data_type = data_type;
+                    }
+                    AlterColumnOperation::AddGenerated {
+                        generated_as,
+                        sequence_options,
+                    } => column.options.push(column_option(ColumnOption::Generated {
+                        generated_as: generated_as.unwrap_or(GeneratedAs::Always),
+                        sequence_options,
+                        generation_expr: None,
+                        generation_expr_mode: None,
+                        generated_keyword: true,
+                    })),
+                }
+            }
+            // MySQL's CHANGE and MODIFY redefine the whole column
+            AlterTableOperation::ChangeColumn {
+                old_name,
+                new_name,
+                data_type,
+                options,
+                ..
+            } => {
+                let index = find_column(table, &old_name)
+                    .ok_or_else(|| missing_column(&table_name, &old_name))?;
+                table.columns[index] = ColumnDef {
+                    name: new_name,
+                    data_type,
+                    options: options.into_iter().map(column_option).collect(),
+                };
+            }
+            AlterTableOperation::ModifyColumn {
+                col_name,
+                data_type,
+                options,
+                ..
+            } => {
+                let index = find_column(table, &col_name)
+                    .ok_or_else(|| missing_column(&table_name, &col_name))?;
+                table.columns[index] = ColumnDef {
+                    name: col_name,
+                    data_type,
+                    options: options.into_iter().map(column_option).collect(),
+                };
+            }
+            AlterTableOperation::AddConstraint(constraint) => table.constraints.push(constraint),
+            AlterTableOperation::DropConstraint { name, .. } => {
+                table
+                    .constraints
+                    .retain(|constraint| constraint_name(constraint) != Some(&name));
+                for column in &mut table.columns {
+                    column
+                        .options
+                        .retain(|opt| opt.name.as_ref() != Some(&name));
+                }
+            }
+            // An unqualified new name keeps the table in its schema
+            AlterTableOperation::RenameTable { table_name } => {
+                match table.name.0.split_last_mut() {
+                    Some((last, [_, ..])) if table_name.0.len() == 1 => {
+                        *last = table_name.0[0].clone();
+                    }
+                    _ => table.name = table_name,
+                }
+            }
+            operation => eprintln!(
+                "Warning: ALTER TABLE `{}`: ignoring unsupported operation `{}`",
+                table_name, operation
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Replays a `COMMENT ON TABLE` or `COMMENT ON COLUMN` statement on the `CREATE TABLE` it
+/// targets; `IS NULL` removes the comment. Comments on other objects are ignored.
+fn comment_on(
+    create_tables: &mut [CreateTable],
+    object_type: CommentObject,
+    name: &ObjectName,
+    comment: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (table_name, column) = match object_type {
+        CommentObject::Table => (object_name(name), None),
+        CommentObject::Column => match split_object_name(name) {
+            (Some(table_name), column) => (table_name, Some(column)),
+            (None, column) => {
+                return Err(format!(
+                    "COMMENT ON COLUMN `{}`: the column must be qualified by its table",
+                    column
+                )
+                .into());
+            }
+        },
+        _ => return Ok(()),
+    };
+    let Some(table) = create_tables
+        .iter_mut()
+        .find(|table| object_name(&table.name) == table_name)
+    else {
+        eprintln!(
+            "Warning: COMMENT ON `{}` does not match any preceding CREATE TABLE statement",
+            object_name(name)
+        );
+        return Ok(());
+    };
+
+    let Some(column) = column else {
+        table.comment = comment.map(CommentDef::WithoutEq);
+        return Ok(());
+    };
+    let index = find_column(table, &Ident::new(&column)).ok_or_else(|| {
+        format!(
+            "COMMENT ON COLUMN `{}`: column `{}` does not exist",
+            object_name(name),
+            column
+        )
+    })?;
+    let options = &mut table.columns[index].options;
+    options.retain(|opt| !matches!(opt.option, ColumnOption::Comment(_)));
+    if let Some(comment) = comment {
+        options.push(column_option(ColumnOption::Comment(comment)));
+    }
+
+    Ok(())
+}
+
+/// Normalizes a comment for doc comments and descriptions: trailing spaces are trimmed from
+/// each line, leading and trailing blank lines are dropped, and an empty comment is `None`.
+fn comment_text(comment: &str) -> Option<String> {
+    let text = comment
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim_matches('\n');
+    (!text.trim().is_empty()).then(|| text.to_string())
+}
+
+/// Returns the position of a column in a `CREATE TABLE` statement.
+fn find_column(table: &CreateTable, name: &Ident) -> Option<usize> {
+    table
+        .columns
+        .iter()
+        .position(|col| col.name.value == name.value)
+}
+
+/// Builds the error of an `ALTER TABLE` naming a column absent from its table.
+fn missing_column(table_name: &str, column: &Ident) -> Box<dyn std::error::Error> {
+    format!(
+        "ALTER TABLE `{}`: column `{}` does not exist",
+        table_name, column.value
+    )
+    .into()
+}
+
+/// Wraps an option added to a column by `ALTER TABLE` into an unnamed column option.
+fn column_option(option: ColumnOption) -> ColumnOptionDef {
+    ColumnOptionDef { name: None, option }
+}
+
+/// Returns the columns a table constraint applies to, `None` for `CHECK` constraints.
+fn constraint_columns(constraint: &mut TableConstraint) -> Option<&mut Vec<Ident>> {
+    match constraint {
+        TableConstraint::Unique { columns, .. }
+        | TableConstraint::PrimaryKey { columns, .. }
+        | TableConstraint::ForeignKey { columns, .. }
+        | TableConstraint::Index { columns, .. }
+        | TableConstraint::FulltextOrSpatial { columns, .. } => Some(columns),
+        TableConstraint::Check { .. } => None,
+    }
+}
+
+/// Returns the name of a table constraint declared with `CONSTRAINT <name>`.
+fn constraint_name(constraint: &TableConstraint) -> Option<&Ident> {
+    match constraint {
+        TableConstraint::Unique { name, .. }
+        | TableConstraint::PrimaryKey { name, .. }
+        | TableConstraint::ForeignKey { name, .. }
+        | TableConstraint::Check { name, .. }
+        | TableConstraint::Index { name, .. } => name.as_ref(),
+        TableConstraint::FulltextOrSpatial { .. } => None,
+    }
+}
+
+/// Resolves the rules of every parsed table, defaults included: module and
+/// struct names (falling back to the table name and its PascalCase form),
+/// skip lists, always-include lists and column type overrides. Rules naming
+/// tables absent from the SQL are reported, and rules naming columns absent
+/// from their table are rejected.
+fn apply_rules(
+    tables: &mut [TableSchema],
+    rules: &Rules,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for table_name in rules.tables.keys() {
+        if !tables.iter().any(|t| t.is_named(table_name)) {
+            eprintln!(
+                "Warning: {}: rules entry `{}` does not match any CREATE TABLE statement",
+                rules.source, table_name
+            );
+        }
+    }
+
+    // Defaults only apply to the tables having their columns, so a typo leaves them unused
+    for (key, list) in rules
+        .defaults
+        .iter()
+        .flat_map(|defaults| defaults.column_lists())
+    {
+        for field in list {
+            if !tables
+                .iter()
+                .any(|t| t.columns.iter().any(|col| &col.name == field))
+            {
+                eprintln!(
+                    "Warning: {}: defaults, key `{}`: column `{}` does not exist in any table",
+                    rules.source, key, field
+                );
+            }
+        }
+    }
+
+    for key in rules.type_overrides.keys() {
+        let Some((table_name, column)) = key.rsplit_once('.') else {
+            continue;
+        };
+        match tables.iter().find(|t| t.is_named(table_name)) {
+            None => eprintln!(
+                "Warning: {}: {} entry `{}` does not match any CREATE TABLE statement",
+                rules.source, TYPE_OVERRIDES_KEY, key
+            ),
+            Some(table) if !table.columns.iter().any(|col| col.name == column) => {
+                return Err(format!(
+                    "{}: {}, key `{}`: column `{}` does not exist in table `{}`",
+                    rules.source, TYPE_OVERRIDES_KEY, key, column, table_name
+                )
+                .into());
+            }
+            Some(_) => {}
+        }
+    }
+
+    for table in tables.iter_mut() {
+        let qualified_name = table.qualified_name();
+        for col in table.columns.iter_mut() {
+            col.type_override = rules
+                .type_override(&qualified_name, &col.name, &col.sql_type)
+                .cloned();
+        }
+
+        let columns = table
+            .columns
+            .iter()
+            .map(|col| col.name.as_str())
+            .collect::<Vec<_>>();
+        let rule = rules.resolve(&qualified_name, &columns)?;
+
+        if let Some(module_name) = rule.module_name {
+            table.module_name = module_name;
+        }
+        if let Some(struct_name) = rule.struct_name {
+            table.struct_name = struct_name;
+        }
+        if rules.group_by_schema {
+            table.schema_module = table
+                .schema_name
+                .as_ref()
+                .map(|schema_name| rust_module_name(&schema_name.replace('.', "_")));
+        }
+
+        table.create_special_fields = rule.create_special_fields;
+        table.update_special_fields = rule.update_special_fields;
+        table.always_include_in_dto = rule.always_include_in_dto;
+        table.update_timestamp_fields = rule.update_timestamp_fields;
+        table.create_user_fields = rule.create_user_fields;
+        table.update_user_fields = rule.update_user_fields;
+        // sqlx has no Decimal support for SQLite
+        table.decimal_as_f64 =
+            table.dialect == SqlDialect::Sqlite || rule.decimal_as_f64.unwrap_or(false);
+
+        for (column, json_type) in rule.json_types.unwrap_or_default() {
+            let col = table
+                .columns
+                .iter_mut()
+                .find(|col| col.name == column)
+                .ok_or_else(|| {
+                    format!(
+                        "{}: table `{}`, key `json_types`: column `{}` does not exist in the table",
+                        rules.source, qualified_name, column
+                    )
+                })?;
+            if !matches!(col.data_type, DataType::JSON | DataType::JSONB) {
+                return Err(format!(
+                    "{}: table `{}`, key `json_types`: column `{}` is {}, not JSON or JSONB",
+                    rules.source, qualified_name, column, col.sql_type
+                )
+                .into());
+            }
+            col.json_type = Some(json_type);
+        }
+
+        for (column, field_name) in rule.field_names.unwrap_or_default() {
+            let col = table
+                .columns
+                .iter_mut()
+                .find(|col| col.name == column)
+                .ok_or_else(|| {
+                    format!(
+                        "{}: table `{}`, key `field_names`: column `{}` does not exist in the table",
+                        rules.source, qualified_name, column
+                    )
+                })?;
+            col.field_name = field_name;
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects columns of a table generating the same Rust field, such as `createdAt` and
+/// `created_at`.
+fn check_field_names(table: &TableSchema) -> Result<(), Box<dyn std::error::Error>> {
+    for (i, col) in table.columns.iter().enumerate() {
+        if let Some(other) = table.columns[..i]
+            .iter()
+            .find(|other| other.field_name == col.field_name)
+        {
+            return Err(format!(
+                "columns `{}` and `{}` of table `{}` both generate field `{}`; set a distinct \
+                 name in the rules' `field_names`",
+                other.name,
+                col.name,
+                table.qualified_name(),
+                col.field_name
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the columns set to the authenticated user are part of the DTO the handler
+/// fills and hold the user id as a `String`.
+fn check_user_fields(table: &TableSchema) -> Result<(), Box<dyn std::error::Error>> {
+    let lists = [
+        (
+            "create_user_fields",
+            &table.create_user_fields,
+            &table.create_special_fields,
+            "create_special_fields",
+        ),
+        (
+            "update_user_fields",
+            &table.update_user_fields,
+            &table.update_special_fields,
+            "update_special_fields",
+        ),
+    ];
+    for (key, fields, skip, skip_key) in lists {
+        for field in fields.iter().flatten() {
+            if skip.as_ref().is_some_and(|skip| skip.contains(field)) {
+                return Err(format!(
+                    "table `{}`, key `{}`: column `{}` is excluded by `{}`, so the handler \
+                     cannot set it",
+                    table.qualified_name(),
+                    key,
+                    field,
+                    skip_key
+                )
+                .into());
+            }
+            if !is_user_column(table, field) {
+                return Err(format!(
+                    "table `{}`, key `{}`: column `{}` does not map to `String`, the type of \
+                     the authenticated user's id",
+                    table.qualified_name(),
+                    key,
+                    field
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the primary key of a table and sets how new rows obtain it, falling back to an `id`
+/// column when the table declares no key.
+fn check_primary_key(table: &mut TableSchema) -> Result<(), Box<dyn std::error::Error>> {
+    if table.primary_key.is_empty() {
+        if !table.columns.iter().any(|col| col.name == "id") {
+            return Err(
+                format!("table `{}` declares no PRIMARY KEY", table.qualified_name()).into(),
+            );
+        }
+        eprintln!(
+            "Warning: table `{}` declares no PRIMARY KEY, assuming `id`",
+            table.qualified_name()
+        );
+        table.primary_key = vec!["id".to_string()];
+    }
+
+    let key_columns = table
+        .primary_key
+        .iter()
+        .map(|name| {
+            table
+                .columns
+                .iter()
+                .find(|col| &col.name == name)
+                .ok_or_else(|| {
+                    format!(
+                        "table `{}`: primary key column `{}` does not exist",
+                        table.qualified_name(),
+                        name
+                    )
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let create_skip = table.create_special_fields.as_deref().unwrap_or(&[]);
+    let pk_strategy = if key_columns
+        .iter()
+        .all(|col| !create_skip.contains(&col.name))
+    {
+        "payload"
+    } else if key_columns.len() > 1 {
+        return Err(format!(
+            "table `{}`: every column of the composite primary key must be part of the Create DTO",
+            table.qualified_name()
+        )
+        .into());
+    } else if key_columns[0].is_generated {
+        // MySQL has no RETURNING: the key is read back with LAST_INSERT_ID(), which only
+        // reports AUTO_INCREMENT values
+        if table.dialect == SqlDialect::MySql
+            && !matches!(
+                column_type(table, key_columns[0]).as_str(),
+                "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64"
+            )
+        {
+            return Err(format!(
+                "table `{}`: primary key `{}` is generated by the database but is not an \
+                 AUTO_INCREMENT integer, so MySQL cannot report it",
+                table.qualified_name(),
+                key_columns[0].name
+            )
+            .into());
+        }
+        "database"
+    } else if column_type(table, key_columns[0]) == "uuid::Uuid" {
+        "uuid"
+    } else if column_type(table, key_columns[0]) == "String" {
+        // The handler generates hyphenated UUIDs, 36 characters long
+        if let Some(length) = declared_length(&key_columns[0].data_type)
+            && length < 36
+        {
+            return Err(format!(
+                "table `{}`: primary key `{}` is excluded from the Create DTO and would be \
+                 generated as a 36-character UUID, but is declared with {} characters",
+                table.qualified_name(),
+                key_columns[0].name,
+                length
+            )
+            .into());
+        }
+        "uuid_string"
+    } else {
+        return Err(format!(
+            "table `{}`: primary key `{}` is excluded from the Create DTO but has no DEFAULT, \
+             identity or serial type to generate it",
+            table.qualified_name(),
+            key_columns[0].name
+        )
+        .into());
+    };
+
+    table.pk_strategy = pk_strategy;
+    Ok(())
+}
+
+/// Rejects multi-dimensional array columns such as `INT[][]`, which sqlx cannot decode, unless
+/// the rules' `type_overrides` map them to a Rust type.
+fn check_array_dimensions(table: &TableSchema) -> Result<(), Box<dyn std::error::Error>> {
+    for col in &table.columns {
+        if let DataType::Array(
+            ArrayElemTypeDef::SquareBracket(element, _)
+            | ArrayElemTypeDef::AngleBracket(element)
+            | ArrayElemTypeDef::Parenthesis(element),
+        ) = &col.data_type
+            && matches!(element.as_ref(), DataType::Array(_))
+            && col.type_override.is_none()
+        {
+            return Err(format!(
+                "table `{}`: column `{}` is a multi-dimensional array ({}), which sqlx cannot \
+                 decode; map it with `type_overrides`",
+                table.qualified_name(),
+                col.name,
+                col.sql_type
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the declared length of a character type, e.g. 32 for `VARCHAR(32)`.
+fn declared_length(data_type: &DataType) -> Option<u64> {
+    match data_type {
+        DataType::Char(length)
+        | DataType::Character(length)
+        | DataType::Varchar(length)
+        | DataType::CharVarying(length)
+        | DataType::CharacterVarying(length)
+        | DataType::Nvarchar(length) => match length {
+            Some(CharacterLength::IntegerLength { length, .. }) => Some(*length),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns whether a column of the table maps to `String`, so it can hold the authenticated
+/// user's id.
+fn is_user_column(table: &TableSchema, name: &str) -> bool {
+    table
+        .columns
+        .iter()
+        .find(|col| col.name == name)
+        .is_some_and(|col| column_type(table, col) == "String")
+}
+
+/// Rejects tables generating the same module, such as `app.todos` and `archive.todos`, whose
+/// files and routes would overwrite each other.
+fn check_module_names(tables: &[TableSchema]) -> Result<(), Box<dyn std::error::Error>> {
+    for (i, table) in tables.iter().enumerate() {
+        if let Some(other) = tables[..i]
+            .iter()
+            .find(|other| other.module_name == table.module_name)
+        {
+            return Err(format!(
+                "tables `{}` and `{}` both generate module `{}`; set a distinct `module_name` \
+                 in the rules",
+                other.qualified_name(),
+                table.qualified_name(),
+                table.module_name
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds a foreign key whose referenced names default to the referenced table name,
+/// without its schema.
+/// A missing column list (`REFERENCES users`) refers to the primary key, assumed to be `id`.
+fn foreign_key(
+    columns: Vec<String>,
+    referenced_table: String,
+    referred_columns: &[sqlparser::ast::Ident],
+) -> ForeignKey {
+    let referenced_columns = if referred_columns.is_empty() {
+        vec!["id".to_string()]
+    } else {
+        referred_columns
+            .iter()
+            .map(|col| col.value.clone())
+            .collect()
+    };
+
+    let base_name = referenced_table.rsplit('.').next().unwrap_or_default();
+
+    ForeignKey {
+        columns,
+        referenced_module: rust_module_name(base_name),
+        referenced_struct: to_pascal_case(base_name),
+        referenced_table,
+        referenced_columns,
+    }
+}
+
+/// Copies the resolved module and struct names of referenced tables onto the foreign keys
+/// pointing at them; tables absent from the SQL keep the names derived from the table name.
+fn resolve_foreign_keys(tables: &mut [TableSchema]) {
+    let names = tables
+        .iter()
+        .map(|t| {
+            (
+                t.table_name.clone(),
+                t.qualified_name(),
+                t.module_name.clone(),
+                t.struct_name.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for table in tables.iter_mut() {
+        for fk in &mut table.foreign_keys {
+            if let Some((_, _, module_name, struct_name)) =
+                names.iter().find(|(table_name, qualified_name, _, _)| {
+                    &fk.referenced_table == table_name || &fk.referenced_table == qualified_name
                 })
-                .collect();
+            {
+                fk.referenced_module = module_name.clone();
+                fk.referenced_struct = struct_name.clone();
+            }
+        }
+    }
+}
 
-            return Ok(TableSchema {
-                table_name: table_name.clone(),
-                columns: parsed_columns,
-                module_name: table_name.clone(),
-                struct_name: to_pascal_case(&table_name),
-                create_special_fields,
-                update_special_fields,
-                always_include_in_dto,
-            });
+/// Builds an enum type from its unquoted name, naming the Rust enum after the name in
+/// PascalCase and each variant after its label in PascalCase.
+fn enum_type(name: String, labels: Vec<String>) -> EnumType {
+    let variants = labels
+        .into_iter()
+        .map(|label| {
+            // Upper-case labels are read as SCREAMING_SNAKE_CASE words
+            let words = if label.chars().any(char::is_lowercase) {
+                label.clone()
+            } else {
+                label.to_lowercase()
+            };
+            let words = words
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect::<String>();
+            let mut name = to_pascal_case(&words);
+            if !name.starts_with(|c: char| c.is_alphabetic()) {
+                name.insert(0, 'V');
+            }
+            EnumVariant { label, name }
+        })
+        .collect::<Vec<_>>();
+
+    EnumType {
+        type_name: to_pascal_case(&rust_module_name(
+            name.rsplit('.').next().unwrap_or_default(),
+        )),
+        rename_all: rename_all_style(&variants),
+        name,
+        variants,
+    }
+}
+
+/// Returns the `rename_all` style reproducing every label from its variant name,
+/// or `None` when the labels need individual renames.
+fn rename_all_style(variants: &[EnumVariant]) -> Option<&'static str> {
+    [
+        "snake_case",
+        "SCREAMING_SNAKE_CASE",
+        "kebab-case",
+        "lowercase",
+        "PascalCase",
+    ]
+    .into_iter()
+    .find(|style| {
+        variants.iter().all(|variant| {
+            let renamed = match *style {
+                "snake_case" => to_snake_case(&variant.name),
+                "SCREAMING_SNAKE_CASE" => to_snake_case(&variant.name).to_uppercase(),
+                "kebab-case" => to_snake_case(&variant.name).replace('_', "-"),
+                "lowercase" => variant.name.to_lowercase(),
+                _ => variant.name.clone(),
+            };
+            renamed == variant.label
+        })
+    })
+}
+
+/// Converts a PascalCase name to snake_case, the way serde's `rename_all` does.
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Rust keywords that cannot name a field as is, strict and reserved (2024 edition).
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Derives the Rust field of a column: mixed-case names are converted to snake_case
+/// (`createdAt` gives `created_at`), other characters invalid in identifiers become `_`, and
+/// keywords are escaped as raw identifiers (`type` gives `r#type`), except those raw
+/// identifiers cannot express, which get a trailing `_` (`self_`).
+fn rust_field_name(column: &str) -> String {
+    let chars = column.chars().collect::<Vec<_>>();
+    let mut field = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            // Start a word at `aB`, `1B` and the last capital of an acronym (`IDs`, `HTTPServer`)
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1).copied();
+            let plural = next == Some('s') && !chars.get(i + 2).is_some_and(|c| c.is_lowercase());
+            if prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(char::is_uppercase)
+                    && next.is_some_and(char::is_lowercase)
+                    && !plural)
+            {
+                field.push('_');
+            }
+            field.extend(c.to_lowercase());
+        } else if c.is_alphanumeric() || c == '_' {
+            field.push(c);
+        } else {
+            field.push('_');
         }
     }
+    if field.is_empty() || field.starts_with(|c: char| c.is_ascii_digit()) {
+        field.insert(0, '_');
+    }
+
+    match field.as_str() {
+        "_" | "self" | "super" | "crate" => format!("{}_", field),
+        _ if RUST_KEYWORDS.contains(&field.as_str()) => format!("r#{}", field),
+        _ => field,
+    }
+}
+
+/// Derives the Rust module of a table the way fields are derived (`"UserRoles"` gives
+/// `user_roles`), except keywords get a trailing `_`, as module names also name files.
+fn rust_module_name(table: &str) -> String {
+    match rust_field_name(table).strip_prefix("r#") {
+        Some(keyword) => format!("{}_", keyword),
+        None => rust_field_name(table),
+    }
+}
+
+/// Returns the enum type a column type or its array elements refer to, matching
+/// schema-qualified names by their last part.
+fn find_enum<'a>(enums: &'a [EnumType], data_type: &DataType) -> Option<&'a EnumType> {
+    let name = match data_type {
+        DataType::Custom(name, _) => name,
+        DataType::Array(
+            ArrayElemTypeDef::SquareBracket(element, _)
+            | ArrayElemTypeDef::AngleBracket(element)
+            | ArrayElemTypeDef::Parenthesis(element),
+        ) => return find_enum(enums, element),
+        _ => return None,
+    };
+    let name = object_name(name);
+    let base_name = name.rsplit('.').next().unwrap_or_default();
+
+    enums.iter().find(|enum_type| {
+        enum_type.name == name || enum_type.name.rsplit('.').next() == Some(base_name)
+    })
+}
+
+/// Returns whether a type is one of the PostgreSQL serial pseudo-types, filled by a sequence.
+fn is_serial(data_type: &DataType) -> bool {
+    let DataType::Custom(name, _) = data_type else {
+        return false;
+    };
+    matches!(
+        name.to_string().to_lowercase().as_str(),
+        "smallserial" | "serial2" | "serial" | "serial4" | "bigserial" | "serial8"
+    )
+}
 
-    Err("No valid CREATE TABLE statement found".into())
+/// Returns whether a column option is MySQL's `AUTO_INCREMENT` or SQLite's `AUTOINCREMENT`.
+fn is_auto_increment(option: &ColumnOption) -> bool {
+    let ColumnOption::DialectSpecific(tokens) = option else {
+        return false;
+    };
+    tokens.iter().any(|token| {
+        matches!(
+            token,
+            Token::Word(word)
+                if matches!(word.keyword, Keyword::AUTO_INCREMENT | Keyword::AUTOINCREMENT)
+        )
+    })
+}
+
+/// Joins the parts of a possibly qualified name without their quotes, so that
+/// `` `todos` `` and `todos` name the same table.
+fn object_name(name: &ObjectName) -> String {
+    name.0
+        .iter()
+        .map(|part| match part {
+            ObjectNamePart::Identifier(ident) => ident.value.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Splits a possibly qualified table name into its schema and its unquoted name:
+/// `app.todos` gives `(Some("app"), "todos")`.
+fn split_object_name(name: &ObjectName) -> (Option<String>, String) {
+    let mut parts = name
+        .0
+        .iter()
+        .map(|part| match part {
+            ObjectNamePart::Identifier(ident) => ident.value.clone(),
+        })
+        .collect::<Vec<_>>();
+    let table_name = parts.pop().unwrap_or_default();
+    let schema_name = (!parts.is_empty()).then(|| parts.join("."));
+    (schema_name, table_name)
+}
+
+/// Converts a constant SQL expression into the equivalent Rust literal,
+/// looking through casts such as `'pending'::varchar`.
+fn literal_value(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Value(value) => match &value.value {
+            Value::SingleQuotedString(s) => Some(format!("{:?}", s)),
+            Value::Number(n, _) => Some(n.to_string()),
+            Value::Boolean(b) => Some(b.to_string()),
+            _ => None,
+        },
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => literal_value(expr).map(|n| format!("-{}", n)),
+        Expr::Cast { expr, .. } | Expr::Nested(expr) => literal_value(expr),
+        _ => None,
+    }
 }
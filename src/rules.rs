@@ -0,0 +1,492 @@
+use serde::Deserialize;
+use serde::de::{Deserializer, MapAccess, Visitor};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::parser::SqlDialect;
+
+/// Keys under which the rules file may declare defaults for every table.
+const DEFAULTS_KEYS: [&str; 2] = ["defaults", "*"];
+
+/// Key under which the rules file declares Rust type overrides.
+pub const TYPE_OVERRIDES_KEY: &str = "type_overrides";
+
+/// Key under which the rules file selects the SQL dialect.
+const DIALECT_KEY: &str = "dialect";
+
+/// Key under which the rules file nests the modules of schema-qualified tables per schema.
+const GROUP_BY_SCHEMA_KEY: &str = "group_by_schema";
+
+/// Generation rules loaded from the rules file, keyed by table name, qualified by its schema
+/// (`app.todos`) or not (`todos`).
+/// The same model backs JSON, YAML and TOML rule files.
+pub struct Rules {
+    /// Path of the rules file, reported in error messages.
+    pub source: String,
+    /// Rules merged into every table, from the `defaults` (or `*`) entry.
+    pub defaults: Option<TableRules>,
+    pub tables: HashMap<String, TableRules>,
+    /// Rust types replacing the generated mapping, keyed by `table.column` or by
+    /// lowercase SQL type (e.g. `jsonb` or `varchar(36)`).
+    pub type_overrides: HashMap<String, TypeOverride>,
+    /// SQL dialect of the schema, unless `--dialect` is given.
+    pub dialect: Option<SqlDialect>,
+    /// Whether the modules of schema-qualified tables are nested in a module per schema.
+    pub group_by_schema: bool,
+}
+
+/// A Rust type replacing the one generated for a column.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TypeOverride {
+    /// Rust type of the column, e.g. `Email` or `sqlx::types::Json<serde_json::Value>`.
+    #[serde(rename = "type")]
+    pub rust_type: String,
+    /// Paths imported by the generated files using the type, e.g. `crate::common::types::Email`.
+    #[serde(default, rename = "use")]
+    pub imports: Vec<String>,
+}
+
+/// Top-level entries of a rules file: the type overrides, dialect and module grouping, then
+/// rules keyed by table name.
+struct RulesFile {
+    tables: HashMap<String, TableRules>,
+    type_overrides: HashMap<String, TypeOverride>,
+    dialect: Option<SqlDialect>,
+    group_by_schema: bool,
+}
+
+impl<'de> Deserialize<'de> for RulesFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RulesFileVisitor;
+
+        impl<'de> Visitor<'de> for RulesFileVisitor {
+            type Value = RulesFile;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of table names to rules")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RulesFile, A::Error> {
+                let mut file = RulesFile {
+                    tables: HashMap::new(),
+                    type_overrides: HashMap::new(),
+                    dialect: None,
+                    group_by_schema: false,
+                };
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == TYPE_OVERRIDES_KEY {
+                        file.type_overrides = map.next_value()?;
+                    } else if key == DIALECT_KEY {
+                        file.dialect = Some(map.next_value()?);
+                    } else if key == GROUP_BY_SCHEMA_KEY {
+                        file.group_by_schema = map.next_value()?;
+                    } else {
+                        let rule = map.next_value()?;
+                        file.tables.insert(key, rule);
+                    }
+                }
+                Ok(file)
+            }
+        }
+
+        deserializer.deserialize_map(RulesFileVisitor)
+    }
+}
+
+/// Rules for a single table.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TableRules {
+    /// Module (folder) name, e.g. `todo`.
+    pub module_name: Option<String>,
+    /// Rust struct name, e.g. `Todo`.
+    pub struct_name: Option<String>,
+    /// Columns excluded from the Create DTO and the INSERT statement.
+    pub create_special_fields: Option<Vec<String>>,
+    /// Columns excluded from the Update DTO and the UPDATE statement.
+    pub update_special_fields: Option<Vec<String>>,
+    /// Columns that stay required (not `Option`) in DTOs, even on update.
+    pub always_include_in_dto: Option<Vec<String>>,
+    /// Columns set to the current time by every UPDATE statement.
+    pub update_timestamp_fields: Option<Vec<String>>,
+    /// Columns set to the authenticated user's id by the create handler, e.g. `created_by`.
+    pub create_user_fields: Option<Vec<String>>,
+    /// Columns set to the authenticated user's id by the create and update handlers,
+    /// e.g. `modified_by`.
+    pub update_user_fields: Option<Vec<String>>,
+    /// Maps NUMERIC/DECIMAL columns to `f64` instead of `rust_decimal::Decimal`.
+    pub decimal_as_f64: Option<bool>,
+    /// Rust types of JSON/JSONB columns, keyed by column, e.g. `{"metadata": "TodoMetadata"}`.
+    pub json_types: Option<HashMap<String, String>>,
+    /// Rust field names of columns, keyed by column, e.g. `{"type": "kind"}`.
+    pub field_names: Option<HashMap<String, String>>,
+    /// Whether the defaults are merged into this table (`true` when omitted).
+    /// Set to `false` to override the defaults with this entry alone.
+    pub inherit_defaults: Option<bool>,
+}
+
+impl TableRules {
+    /// Returns the columns named by each key of the rules.
+    pub fn column_lists(&self) -> Vec<(&'static str, Vec<&String>)> {
+        let mut lists = [
+            ("create_special_fields", &self.create_special_fields),
+            ("update_special_fields", &self.update_special_fields),
+            ("always_include_in_dto", &self.always_include_in_dto),
+            ("update_timestamp_fields", &self.update_timestamp_fields),
+            ("create_user_fields", &self.create_user_fields),
+            ("update_user_fields", &self.update_user_fields),
+        ]
+        .into_iter()
+        .filter_map(|(key, list)| list.as_ref().map(|list| (key, list.iter().collect())))
+        .collect::<Vec<_>>();
+        if let Some(json_types) = &self.json_types {
+            lists.push(("json_types", json_types.keys().collect()));
+        }
+        if let Some(field_names) = &self.field_names {
+            lists.push(("field_names", field_names.keys().collect()));
+        }
+        lists
+    }
+
+    /// Keeps only the listed columns that exist in the table, so defaults
+    /// naming audit columns apply to the tables that actually have them.
+    fn retain_columns(&self, columns: &[&str]) -> TableRules {
+        let retain = |list: &Option<Vec<String>>| {
+            list.as_ref().map(|list| {
+                list.iter()
+                    .filter(|field| columns.contains(&field.as_str()))
+                    .cloned()
+                    .collect()
+            })
+        };
+
+        let retain_map = |map: &Option<HashMap<String, String>>| {
+            map.as_ref().map(|map| {
+                map.iter()
+                    .filter(|(field, _)| columns.contains(&field.as_str()))
+                    .map(|(field, value)| (field.clone(), value.clone()))
+                    .collect()
+            })
+        };
+
+        TableRules {
+            create_special_fields: retain(&self.create_special_fields),
+            update_special_fields: retain(&self.update_special_fields),
+            always_include_in_dto: retain(&self.always_include_in_dto),
+            update_timestamp_fields: retain(&self.update_timestamp_fields),
+            create_user_fields: retain(&self.create_user_fields),
+            update_user_fields: retain(&self.update_user_fields),
+            json_types: retain_map(&self.json_types),
+            field_names: retain_map(&self.field_names),
+            ..self.clone()
+        }
+    }
+
+    /// Merges `other` on top of these rules: names and flags are overridden,
+    /// column lists, JSON types and field names are extended.
+    fn merge(&mut self, other: &TableRules) {
+        let extend = |base: &mut Option<Vec<String>>, extra: &Option<Vec<String>>| {
+            if let Some(extra) = extra {
+                let list = base.get_or_insert_with(Vec::new);
+                for field in extra {
+                    if !list.contains(field) {
+                        list.push(field.clone());
+                    }
+                }
+            }
+        };
+
+        if other.module_name.is_some() {
+            self.module_name = other.module_name.clone();
+        }
+        if other.struct_name.is_some() {
+            self.struct_name = other.struct_name.clone();
+        }
+        if other.decimal_as_f64.is_some() {
+            self.decimal_as_f64 = other.decimal_as_f64;
+        }
+        extend(
+            &mut self.create_special_fields,
+            &other.create_special_fields,
+        );
+        extend(
+            &mut self.update_special_fields,
+            &other.update_special_fields,
+        );
+        extend(
+            &mut self.always_include_in_dto,
+            &other.always_include_in_dto,
+        );
+        extend(
+            &mut self.update_timestamp_fields,
+            &other.update_timestamp_fields,
+        );
+        extend(&mut self.create_user_fields, &other.create_user_fields);
+        extend(&mut self.update_user_fields, &other.update_user_fields);
+        if let Some(json_types) = &other.json_types {
+            self.json_types
+                .get_or_insert_with(HashMap::new)
+                .extend(json_types.clone());
+        }
+        if let Some(field_names) = &other.field_names {
+            self.field_names
+                .get_or_insert_with(HashMap::new)
+                .extend(field_names.clone());
+        }
+    }
+}
+
+impl Rules {
+    /// Returns the effective rules of a table: the defaults merged with the
+    /// table's own entry, after checking that every column named by the
+    /// table's entry exists in the table. The entry keyed by the qualified
+    /// table name (`app.todos`) wins over the one keyed by its bare name.
+    pub fn resolve(
+        &self,
+        table_name: &str,
+        columns: &[&str],
+    ) -> Result<TableRules, Box<dyn std::error::Error>> {
+        let rule = self
+            .tables
+            .get(table_name)
+            .or_else(|| self.tables.get(base_name(table_name)));
+        if let Some(rule) = rule {
+            self.validate_columns(table_name, rule, columns)?;
+        }
+
+        let mut resolved = TableRules::default();
+        if let Some(defaults) = &self.defaults
+            && rule.is_none_or(|rule| rule.inherit_defaults.unwrap_or(true))
+        {
+            resolved = defaults.retain_columns(columns);
+        }
+        if let Some(rule) = rule {
+            resolved.merge(rule);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Checks that every column named in the table's rules exists in the table.
+    fn validate_columns(
+        &self,
+        table_name: &str,
+        rule: &TableRules,
+        columns: &[&str],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for (key, list) in rule.column_lists() {
+            for field in list {
+                if !columns.contains(&field.as_str()) {
+                    return Err(format!(
+                        "{}: table `{}`, key `{}`: column `{}` does not exist in the table",
+                        self.source, table_name, key, field
+                    )
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the type override of a column: the one keyed by `table.column`, with the table
+    /// name qualified or not, else the one keyed by its SQL type, written in full
+    /// (`varchar(36)`) or without arguments (`varchar`), under any of its aliases.
+    pub fn type_override(
+        &self,
+        table_name: &str,
+        column: &str,
+        sql_type: &str,
+    ) -> Option<&TypeOverride> {
+        let sql_type = canonical_sql_type(sql_type);
+        let base_type = sql_type.split('(').next().unwrap_or_default();
+
+        self.type_overrides
+            .get(&format!("{}.{}", table_name, column))
+            .or_else(|| {
+                self.type_overrides
+                    .get(&format!("{}.{}", base_name(table_name), column))
+            })
+            .or_else(|| self.type_overrides.get(&sql_type))
+            .or_else(|| self.type_overrides.get(base_type))
+    }
+}
+
+/// Returns the canonical spelling of a SQL type, so that aliases match the same type overrides:
+/// lowercase, with single spaces, no spaces around arguments, and PostgreSQL's aliases replaced
+/// by a single name, e.g. `timestamptz(3)` for `TIMESTAMP(3) WITH TIME ZONE`.
+fn canonical_sql_type(sql_type: &str) -> String {
+    let sql_type = sql_type
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" (", "(")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(", ", ",")
+        .replace(" ,", ",");
+
+    // Arguments may precede the time zone of a timestamp, e.g. `timestamp(3) with time zone`
+    let (name, arguments) = match (sql_type.find('('), sql_type.find(')')) {
+        (Some(start), Some(end)) if start < end => (
+            format!("{} {}", &sql_type[..start], &sql_type[end + 1..])
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+            &sql_type[start..=end],
+        ),
+        _ => (sql_type.clone(), ""),
+    };
+
+    let name = match name.as_str() {
+        "int" | "int4" | "integer" => "int",
+        "int2" | "smallint" => "smallint",
+        "int8" | "bigint" => "bigint",
+        "serial4" | "serial" => "serial",
+        "serial2" | "smallserial" => "smallserial",
+        "serial8" | "bigserial" => "bigserial",
+        "float4" | "real" => "real",
+        "float8" | "double precision" => "double precision",
+        "decimal" | "numeric" => "numeric",
+        "bool" | "boolean" => "boolean",
+        "character varying" | "varchar" => "varchar",
+        "character" | "char" => "char",
+        "timestamp with time zone" | "timestamptz" => "timestamptz",
+        "timestamp without time zone" | "timestamp" => "timestamp",
+        "time with time zone" | "timetz" => "timetz",
+        "time without time zone" | "time" => "time",
+        name => name,
+    };
+
+    format!("{}{}", name, arguments)
+}
+
+/// Returns a table name without its schema: `todos` for `app.todos`.
+fn base_name(table_name: &str) -> &str {
+    table_name.rsplit('.').next().unwrap_or_default()
+}
+
+/// Reads and deserializes the rules file at `path`, choosing the format by
+/// extension: `.json`, `.yaml`/`.yml` or `.toml`.
+pub fn load_rules(path: &str) -> Result<Rules, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("{}: failed to read rules file: {}", path, err))?;
+
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+
+    let file: RulesFile = match extension.as_deref() {
+        Some("json") => {
+            let deserializer = &mut serde_json::Deserializer::from_str(&text);
+            serde_path_to_error::deserialize(deserializer)
+                .map_err(|err| describe_error(path, err.path(), err.inner()))?
+        }
+        Some("yaml") | Some("yml") => {
+            let deserializer = serde_yaml::Deserializer::from_str(&text);
+            serde_path_to_error::deserialize(deserializer)
+                .map_err(|err| describe_error(path, err.path(), err.inner()))?
+        }
+        Some("toml") => {
+            let deserializer = toml::Deserializer::new(&text);
+            serde_path_to_error::deserialize(deserializer)
+                .map_err(|err| describe_error(path, err.path(), err.inner()))?
+        }
+        _ => {
+            return Err(format!(
+                "{}: unsupported rules file extension (expected .json, .yaml, .yml or .toml)",
+                path
+            )
+            .into());
+        }
+    };
+
+    let mut tables = file.tables;
+    let mut defaults = None;
+    for key in DEFAULTS_KEYS {
+        if let Some(rule) = tables.remove(key) {
+            if defaults.is_some() {
+                return Err(format!(
+                    "{}: declare defaults under either `defaults` or `*`, not both",
+                    path
+                )
+                .into());
+            }
+            if rule.module_name.is_some()
+                || rule.struct_name.is_some()
+                || rule.inherit_defaults.is_some()
+            {
+                return Err(format!(
+                    "{}: table `{}`: `module_name`, `struct_name` and `inherit_defaults` cannot be set in the defaults",
+                    path, key
+                )
+                .into());
+            }
+            defaults = Some(rule);
+        }
+    }
+
+    // SQL types are matched by their canonical name; `table.column` keys are kept as written
+    let type_overrides = file
+        .type_overrides
+        .into_iter()
+        .map(|(key, type_override)| {
+            if key.contains('.') {
+                (key, type_override)
+            } else {
+                (canonical_sql_type(&key), type_override)
+            }
+        })
+        .collect();
+
+    Ok(Rules {
+        source: path.to_string(),
+        defaults,
+        tables,
+        type_overrides,
+        dialect: file.dialect,
+        group_by_schema: file.group_by_schema,
+    })
+}
+
+/// Builds an error message naming the rules file, table and key at fault.
+fn describe_error(
+    source: &str,
+    path: &serde_path_to_error::Path,
+    err: &dyn std::fmt::Display,
+) -> String {
+    let mut segments = path.iter();
+    let Some(table) = segments.next() else {
+        return format!("{}: {}", source, err);
+    };
+    let table = table.to_string();
+    if table == DIALECT_KEY || table == GROUP_BY_SCHEMA_KEY {
+        return format!("{}: `{}`: {}", source, table, err);
+    }
+    if table == TYPE_OVERRIDES_KEY {
+        let key = segments
+            .map(|segment| segment.to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+        return format!("{}: {}, key `{}`: {}", source, TYPE_OVERRIDES_KEY, key, err);
+    }
+
+    let mut key = String::new();
+    for segment in segments {
+        if !key.is_empty() && !matches!(segment, serde_path_to_error::Segment::Seq { .. }) {
+            key.push('.');
+        }
+        key.push_str(&segment.to_string());
+    }
+
+    if key.is_empty() {
+        format!("{}: table `{}`: {}", source, table, err)
+    } else {
+        format!("{}: table `{}`, key `{}`: {}", source, table, key, err)
+    }
+}